@@ -20,13 +20,14 @@ use std::ops::Bound;
 use crate::animation::AnimationState::Walk;
 use crate::animation::{AnimationState, AnimatorController};
 use crate::components::{
-    AbilityLevel, BaseMoveSpeed, DamageOnTouch, Enemy, FollowPlayer, GainXPOnTouch, Health,
-    Lifetime, MoveSpeed, PassiveXPMultiplier, Player, XPMultiplier, XP,
+    AbilityLevel, BaseMana, BaseMaxHealth, BaseMoveSpeed, CritBonus, DamageOnTouch, Enemy, FollowPlayer, GainXPOnTouch, Health,
+    HitCooldown, Invulnerable, KnockbackResistance, Lifetime, Mana, MaxHealth, MoveSpeed, PassiveXPMultiplier, Player, Resistances, SpecialAmmoQueue, StatusImmunities, XP,
 };
-use crate::constants::{CORPSE_LAYER, ENEMY_LAYER, PLAYER_LAYER, PLAYER_SPEED, XP_LAYER};
+use crate::constants::{CORPSE_LAYER, DEFAULT_TOUCH_KNOCKBACK, ENEMY_LAYER, ENEMY_TOUCH_HIT_COOLDOWN, PLAYER_HEALTH, PLAYER_I_FRAMES, PLAYER_LAYER, PLAYER_MANA, PLAYER_MANA_REGEN, PLAYER_SPEED, XP_LAYER};
 use crate::initialization::load_prefabs::{load_enemy_data_from_path, Atlases, Enemies};
 use crate::physics::layers::game_layer;
 use crate::systems::animation::AnimationState::{Dead, Idle};
+use crate::systems::modifiers::PlayerStats;
 use crate::systems::spawning::LevelBounds;
 
 const XP_COLOR: Color = Color::rgb(0.0, 1.0, 0.1);
@@ -36,14 +37,13 @@ pub enum Object {
     Cube,
     Player,
     Enemy,
-    Fireball,
-    PistolBullet,
-    Iceball,
+    Projectile,
     Flask,
     DamageNumber,
     Corpse,
     XP,
     Particle,
+    Orbit,
 }
 
 #[derive(Component)]
@@ -55,13 +55,20 @@ pub struct PlayerBundle {
     pub name: Name,
     pub player: Player,
     pub health: Health,
+    pub max_health: MaxHealth,
+    pub base_max_health: BaseMaxHealth,
+    pub mana: Mana,
+    pub base_mana: BaseMana,
     pub physical: PhysicalBundle,
     pub animator: AnimatorController,
     pub xp: XP,
     move_speed: MoveSpeed,
     pub base_speed: BaseMoveSpeed,
     pub worldly: Worldly,
-    pub xp_mult: XPMultiplier,
+    pub stats: PlayerStats,
+    pub crit_bonus: CritBonus,
+    pub special_ammo: SpecialAmmoQueue,
+    pub invulnerable: Invulnerable,
 }
 
 #[derive(LdtkEntity, Component, Default)]
@@ -89,7 +96,18 @@ impl Default for PlayerBundle {
 
             name: Name::new("Player"),
             player: Default::default(),
-            health: Health { value: 100.0 },
+            health: Health { value: PLAYER_HEALTH },
+            max_health: MaxHealth { value: PLAYER_HEALTH },
+            base_max_health: BaseMaxHealth { value: PLAYER_HEALTH },
+            mana: Mana {
+                value: PLAYER_MANA,
+                max: PLAYER_MANA,
+                regen_per_second: PLAYER_MANA_REGEN,
+            },
+            base_mana: BaseMana {
+                max: PLAYER_MANA,
+                regen_per_second: PLAYER_MANA_REGEN,
+            },
             animator: AnimatorController {
                 state: AnimationState::Walk,
                 name: "default".to_string(),
@@ -100,7 +118,10 @@ impl Default for PlayerBundle {
                 value: PLAYER_SPEED,
             },
             worldly: Default::default(),
-            xp_mult: Default::default(),
+            stats: Default::default(),
+            crit_bonus: Default::default(),
+            special_ammo: Default::default(),
+            invulnerable: Invulnerable::from_seconds(PLAYER_I_FRAMES),
         }
     }
 }
@@ -135,7 +156,18 @@ impl PlayerBundle {
             },
             name: Name::new("Player"),
             player: Default::default(),
-            health: Health { value: 100.0 },
+            health: Health { value: PLAYER_HEALTH },
+            max_health: MaxHealth { value: PLAYER_HEALTH },
+            base_max_health: BaseMaxHealth { value: PLAYER_HEALTH },
+            mana: Mana {
+                value: PLAYER_MANA,
+                max: PLAYER_MANA,
+                regen_per_second: PLAYER_MANA_REGEN,
+            },
+            base_mana: BaseMana {
+                max: PLAYER_MANA,
+                regen_per_second: PLAYER_MANA_REGEN,
+            },
             animator: AnimatorController {
                 state: Idle,
                 name: "player".to_string(),
@@ -147,7 +179,10 @@ impl PlayerBundle {
                 value: PLAYER_SPEED,
             },
             worldly: Default::default(),
-            xp_mult: Default::default(),
+            stats: Default::default(),
+            crit_bonus: Default::default(),
+            special_ammo: Default::default(),
+            invulnerable: Invulnerable::from_seconds(PLAYER_I_FRAMES),
         }
     }
 }
@@ -227,6 +262,19 @@ pub struct EnemyData {
     base_move_speed: BaseMoveSpeed,
     health: Health,
     touch_damage: DamageOnTouch,
+    //an enemy that gets knocked back and touches the player again shouldn't hit again right away
+    #[serde(skip, default = "enemy_touch_hit_cooldown")]
+    touch_hit_cooldown: HitCooldown,
+    #[serde(default)]
+    immunities: StatusImmunities,
+    #[serde(default)]
+    resistances: Resistances,
+    #[serde(default)]
+    knockback_resistance: KnockbackResistance,
+}
+
+fn enemy_touch_hit_cooldown() -> HitCooldown {
+    HitCooldown::from_seconds(ENEMY_TOUCH_HIT_COOLDOWN)
 }
 
 pub struct AbilityBundle {
@@ -293,8 +341,13 @@ impl Default for EnemyBundle {
                 health: Health { value: 5.0 },
                 touch_damage: DamageOnTouch {
                     value: 1.0,
+                    knockback: DEFAULT_TOUCH_KNOCKBACK,
                     ..default()
                 },
+                touch_hit_cooldown: enemy_touch_hit_cooldown(),
+                immunities: Default::default(),
+                resistances: Default::default(),
+                knockback_resistance: Default::default(),
             },
             // sensor: Default::default(),
             animator: AnimatorController {
@@ -1,14 +1,17 @@
 use crate::bundles::PhysicalBundle;
-use crate::systems::guns::LevelableData;
+use crate::constants::DEFAULT_TOUCH_KNOCKBACK;
+use crate::initialization::load_prefabs::GunData;
+use crate::systems::guns::{GunLevelableData, LevelableData};
 use bevy::prelude::Component;
 use bevy::prelude::Reflect;
 use bevy::prelude::TimerMode::Once;
 use bevy::prelude::*;
 use bevy::time::TimerMode::Repeating;
 use bevy_asepritesheet::prelude::AnimatedSpriteBundle;
-use bevy_rapier2d::geometry::Sensor;
+use bevy_rapier2d::geometry::{ActiveEvents, Collider, CollisionGroups, Sensor};
 use serde::Deserialize;
 use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
 use std::time::Duration;
 use bevy_rapier2d::prelude::CollidingEntities;
 use temporary_component_derive::TemporaryComponent;
@@ -48,18 +51,11 @@ impl Cooldown {
 pub struct Flask {}
 
 #[derive(Component, Copy, Clone, Debug, Serialize, Deserialize, Default)]
-pub struct PassiveMoveSpeedMultiplier {
-    pub value: f32,
-}
+pub struct PassiveMoveSpeedMultiplier {}
 
 #[derive(Component, Copy, Clone, Debug, Serialize, Deserialize, Default, Reflect)]
 pub struct PassiveXPMultiplier {}
 
-#[derive(Component, Copy, Clone, Debug, Serialize, Deserialize, Default, Reflect)]
-pub struct XPMultiplier {
-    pub value: f32,
-}
-
 #[derive(Component, Copy, Clone, Debug, Serialize, Deserialize, Default, Reflect)]
 pub struct XPPickupRadius {
     pub radius: f32,
@@ -71,26 +67,154 @@ impl LevelableData for XPPickupRadius {
         }
     }
 }
-impl LevelableData for XPMultiplier {
+
+/// Marks the level-up choice that grants [`CritBonus`].
+#[derive(Component, Copy, Clone, Debug, Serialize, Deserialize, Default, Reflect)]
+pub struct PassiveCritBonus {}
+
+/// Added on top of the crit stats of every weapon the player fires.
+#[derive(Component, Copy, Clone, Debug, Serialize, Deserialize, Default, Reflect)]
+pub struct CritBonus {
+    pub chance: f32,
+    pub multiplier: f32,
+}
+
+impl LevelableData for CritBonus {
     fn get_data_for_level(level: u8) -> Self {
         Self {
-            value: level as f32 * (0.1),
+            chance: 0.05 * level as f32,
+            multiplier: 0.1 * level as f32,
         }
     }
 }
 
-impl LevelableData for PassiveMoveSpeedMultiplier {
-    fn get_data_for_level(level: u8) -> Self {
+#[derive(Component, Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct FireBallGun {}
+#[derive(Component, Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct PistolGun {
+}
+
+#[derive(Component, Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct ShotgunGun {}
+
+#[derive(Component, Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct SniperGun {}
+
+#[derive(Serialize, Deserialize, Reflect, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TargetingStrategy {
+    #[default]
+    Nearest,
+    ManualAim,
+    RandomInRange,
+    HighestHealth,
+    DensestCluster,
+    FurthestInRange,
+}
+
+impl TargetingStrategy {
+    pub const ALL: [TargetingStrategy; 6] = [
+        TargetingStrategy::Nearest,
+        TargetingStrategy::ManualAim,
+        TargetingStrategy::RandomInRange,
+        TargetingStrategy::HighestHealth,
+        TargetingStrategy::DensestCluster,
+        TargetingStrategy::FurthestInRange,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TargetingStrategy::Nearest => "Nearest",
+            TargetingStrategy::ManualAim => "Manual Aim",
+            TargetingStrategy::RandomInRange => "Random",
+            TargetingStrategy::HighestHealth => "Highest Health",
+            TargetingStrategy::DensestCluster => "Densest Cluster",
+            TargetingStrategy::FurthestInRange => "Furthest",
+        }
+    }
+}
+
+/// How a weapon picks what to shoot at. Starts out as whatever its gun prefab says,
+/// but can be swapped at runtime.
+#[derive(Component, Serialize, Deserialize, Reflect, Clone, Copy, Debug, PartialEq)]
+pub struct Targeting {
+    pub strategy: TargetingStrategy,
+    pub range: f32,
+}
+
+impl Default for Targeting {
+    fn default() -> Self {
         Self {
-            value: 0.10 * level as f32,
+            strategy: TargetingStrategy::Nearest,
+            range: 600.0,
         }
     }
 }
 
 #[derive(Component, Copy, Clone, Debug, Serialize, Deserialize)]
-pub struct FireBallGun {}
+pub struct OrbitGun {}
+
 #[derive(Component, Copy, Clone, Debug, Serialize, Deserialize)]
-pub struct PistolGun {
+pub struct ChainLightningGun {}
+
+/// Spell that loads every gun at once.
+#[derive(Component, Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct SummonMunition {}
+
+/// Per-level stats of summon munition.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct SummonMunitionData {
+    pub cooldown_seconds: f32,
+    //special rounds loaded on top of a full magazine
+    pub overfill: u16,
+}
+
+impl GunLevelableData for SummonMunitionData {
+    fn from_gun_data(gun: &GunData, level: u8) -> Self {
+        Self {
+            cooldown_seconds: gun.cooldown_for_level(level),
+            overfill: gun.stat("overfill", level) as u16,
+        }
+    }
+}
+
+/// Takes up a hand, in exchange for spell slots.
+#[derive(Component, Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct Tome {}
+
+/// Per-level stats of the orbit weapon.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct OrbitData {
+    pub count: u8,
+    pub radius: f32,
+    //radians per second
+    pub angular_speed: f32,
+    //how long the projectiles stay out every cycle, the gun's cooldown is uptime plus downtime
+    pub uptime_seconds: f32,
+    pub damage: f32,
+    pub hit_cooldown_seconds: f32,
+}
+
+impl GunLevelableData for OrbitData {
+    fn from_gun_data(gun: &GunData, level: u8) -> Self {
+        Self {
+            count: gun.stat("count", level) as u8,
+            radius: gun.stat("radius", level),
+            angular_speed: gun.stat("angular_speed", level),
+            uptime_seconds: gun.stat("uptime", level),
+            damage: gun.stat("damage", level),
+            hit_cooldown_seconds: gun.stat("hit_cooldown", level),
+        }
+    }
+}
+
+/// Circles its parent. Despawns itself once `uptime` runs out.
+#[derive(Component, Clone, Debug)]
+pub struct Orbiting {
+    pub angle: f32,
+    pub radius: f32,
+    pub angular_speed: f32,
+    pub uptime: Timer,
 }
 
 #[derive(Component, Copy, Clone, Debug, Serialize, Deserialize)]
@@ -101,6 +225,21 @@ pub struct Reloadable {
 #[derive(Component, Reflect, Serialize, Deserialize, Clone, Default)]
 pub struct Reloading {
     pub timer : Timer,
+    //how long until the whole cylinder is loaded, the active reload window is a fraction of this
+    pub total : Timer,
+}
+
+/// A gun that missed its active reload. Can't shoot or reload until this wears off.
+#[derive(Component, Reflect, Serialize, Deserialize, Clone, Default, TemporaryComponent)]
+pub struct Jammed {
+    pub timer : Timer,
+}
+
+/// Granted to a gun by hitting its active reload window.
+#[derive(Component, Reflect, Serialize, Deserialize, Clone, Default, TemporaryComponent)]
+pub struct ActiveReloadBuff {
+    pub damage_multiplier : f32,
+    pub timer : Timer,
 }
 #[derive(Component, Reflect, Serialize, Deserialize, Clone, Default, TemporaryComponent)]
 pub struct Dashing {
@@ -117,6 +256,51 @@ pub struct Ammo {
 #[derive(Component, Copy, Clone, Debug, Serialize, Deserialize)]
 pub struct Chambered {
 }
+
+/// Carried by a `Chambered` bullet next to the marker, changes what the bullet does once it is fired.
+#[derive(Component, Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Reflect)]
+pub enum SpecialRound {
+    Frozen,
+    Incendiary,
+    Explosive,
+    Piercing,
+}
+
+impl SpecialRound {
+    pub fn color(&self) -> Color {
+        match self {
+            SpecialRound::Frozen => Color::rgb(0.2, 0.4, 1.0),
+            SpecialRound::Incendiary => Color::rgb(1.0, 0.5, 0.1),
+            SpecialRound::Explosive => Color::rgb(1.0, 0.1, 0.1),
+            SpecialRound::Piercing => Color::rgb(0.3, 1.0, 0.4),
+        }
+    }
+}
+
+pub const MAX_QUEUED_SPECIAL_ROUNDS: usize = 12;
+
+/// Special rounds waiting to be loaded, oldest first. Lives on the player.
+#[derive(Component, Clone, Debug, Default)]
+pub struct SpecialAmmoQueue {
+    pub rounds: VecDeque<SpecialRound>,
+}
+
+impl SpecialAmmoQueue {
+    /// Rounds past the limit are lost.
+    pub fn push(&mut self, round: SpecialRound) {
+        if self.rounds.len() < MAX_QUEUED_SPECIAL_ROUNDS {
+            self.rounds.push_back(round);
+        }
+    }
+
+    pub fn pop(&mut self) -> Option<SpecialRound> {
+        self.rounds.pop_front()
+    }
+}
+
+/// Guns with this pull from the player's [`SpecialAmmoQueue`] when reloading.
+#[derive(Component, Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct LoadsSpecialRounds {}
 #[derive(Component, Copy, Clone, Debug, Serialize, Deserialize)]
 pub struct IceBallGun {}
 
@@ -126,10 +310,6 @@ pub struct AbilityLevel {
     pub description: String,
 }
 
-#[derive(Component, Copy, Clone, Debug, Serialize, Deserialize)]
-pub struct AttackSpeed {
-    pub percent: f32,
-}
 
 impl Default for Cooldown {
     fn default() -> Self {
@@ -152,6 +332,45 @@ impl Cooldown {
 pub struct Bullet {
     pub hits: u8,
     pub pierce: u8,
+    //how many more times it can ricochet, only used with Ricochet
+    pub bounces: u8,
+}
+
+/// Bullets with this bounce off walls instead of breaking,
+/// and jump to the next enemy within `range` after a hit while they have bounces left.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Ricochet {
+    pub range: f32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HomingTarget {
+    //whatever enemy is closest right now
+    #[default]
+    Nearest,
+    //stick with the first enemy it locked on to until that one dies
+    Original,
+}
+
+/// Steers a projectile's velocity towards an enemy, turning at most `turn_rate` radians per second.
+#[derive(Component, Clone, Debug)]
+pub struct Homing {
+    pub turn_rate: f32,
+    pub range: f32,
+    pub mode: HomingTarget,
+    pub target: Option<Entity>,
+}
+
+impl Homing {
+    pub fn new(turn_rate: f32, range: f32, mode: HomingTarget) -> Self {
+        Self {
+            turn_rate,
+            range,
+            mode,
+            target: None,
+        }
+    }
 }
 
 #[derive(Component, Default)]
@@ -170,11 +389,71 @@ impl Lifetime {
 #[derive(Component)]
 pub struct Expired {}
 
+#[derive(Serialize, Deserialize, Clone)]
+pub enum ExplosionStatus {
+    Burn(ApplyBurnOnTouch),
+    Cold(ApplyColdOnTouch),
+}
+
+/// Damages every enemy within `radius` once the projectile carrying it expires.
+/// Enemies at the edge of the blast take `damage * min_multiplier`.
+#[derive(Component, Serialize, Deserialize, Clone)]
+pub struct ExplodeOnExpire {
+    pub radius: f32,
+    pub damage: f32,
+    pub damage_type: DamageType,
+    pub min_multiplier: f32,
+    #[serde(default)]
+    pub knockback: f32,
+    pub status: Option<ExplosionStatus>,
+}
+
+/// Scales `DamageOnTouch` down the further a projectile has travelled from where it was fired.
+#[derive(Component, Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct DamageFalloff {
+    pub origin: Vec2,
+    pub base_damage: f32,
+    pub start_distance: f32,
+    pub end_distance: f32,
+    pub min_multiplier: f32,
+}
+
 #[derive(Component, Serialize, Deserialize, Clone)]
 pub struct Health {
     pub value: f32,
 }
 
+/// Only the player has a max, nothing heals enemies.
+#[derive(Component, Reflect, Serialize, Deserialize, Clone)]
+pub struct MaxHealth {
+    pub value: f32,
+}
+
+#[derive(Component, Reflect, Serialize, Deserialize, Clone)]
+pub struct BaseMaxHealth {
+    pub value: f32,
+}
+
+/// Spells with a [`ManaCost`] draw from this, max and regen come from player stats.
+#[derive(Component, Reflect, Serialize, Deserialize, Clone)]
+pub struct Mana {
+    pub value: f32,
+    pub max: f32,
+    pub regen_per_second: f32,
+}
+
+#[derive(Component, Reflect, Serialize, Deserialize, Clone)]
+pub struct BaseMana {
+    pub max: f32,
+    pub regen_per_second: f32,
+}
+
+/// Spent every time the spell fires. Its cooldown holds while there isn't enough mana.
+#[derive(Component, Copy, Clone, Debug, Serialize, Deserialize, Reflect)]
+pub struct ManaCost {
+    pub amount: f32,
+}
+
 #[derive(Component, Serialize, Deserialize, Clone)]
 pub struct FollowPlayer;
 
@@ -189,9 +468,45 @@ pub struct Cold {
     pub timer: Timer,
 }
 
+#[derive(Component, Reflect, Serialize, Deserialize, Clone, Default, TemporaryComponent)]
+pub struct Burning {
+    pub damage_per_tick: f32,
+    pub tick_timer: Timer,
+    pub timer: Timer,
+}
+
+/// What happens when a status is applied to an entity that already has it.
+#[derive(Reflect, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum StatusStacking {
+    /// Restart the duration and take the new strength.
+    #[default]
+    Refresh,
+    /// Add the new duration to what is left, keep the stronger of the two.
+    Extend,
+    /// Restart the duration and add the new strength on top, up to `max_stacks` applications worth.
+    Stack { max_stacks: u32 },
+    /// Only replace the current status if the new one is at least as strong.
+    Strongest,
+}
+
+/// Statuses (by [`crate::systems::status_effects::StatusEffect::NAME`]) that can't be applied to this entity.
+#[derive(Component, Serialize, Deserialize, Clone, Debug, Default)]
+pub struct StatusImmunities {
+    pub statuses: Vec<String>,
+}
+
+impl StatusImmunities {
+    pub fn is_immune_to(&self, status: &str) -> bool {
+        self.statuses.iter().any(|immunity| immunity == status)
+    }
+}
+
 pub trait TemporaryComponent {
     fn advance_timer(&mut self, duration: Duration);
     fn is_finished(&self) -> bool;
+    fn timer(&self) -> &Timer;
+    fn timer_mut(&mut self) -> &mut Timer;
 }
 
 #[derive(Component, Reflect, Serialize, Deserialize, Clone)]
@@ -199,38 +514,182 @@ pub struct BaseMoveSpeed {
     pub value: f32,
 }
 
-#[derive(Component, Reflect, Serialize, Deserialize, Clone)]
-pub struct ParentMoveSpeedMultiplier {
-    pub value: f32,
-}
 
 #[derive(Component, Serialize, Deserialize, Clone)]
 pub struct Enemy {
     pub xp: u32,
 }
 
+#[derive(Serialize, Deserialize, Reflect, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum DamageType {
+    #[default]
+    Physical,
+    Fire,
+    Ice,
+    Magic,
+}
+
+impl DamageType {
+    /// Color of the damage numbers for this type.
+    pub fn color(&self) -> Color {
+        match self {
+            DamageType::Physical => Color::WHITE,
+            DamageType::Fire => Color::rgb(1.0, 0.5, 0.1),
+            DamageType::Ice => Color::rgb(0.4, 0.7, 1.0),
+            DamageType::Magic => Color::rgb(0.8, 0.3, 1.0),
+        }
+    }
+}
+
+/// Chance is 0..1, multiplier is applied to the damage of a crit.
+#[derive(Serialize, Deserialize, Reflect, Clone, Copy, Debug, PartialEq)]
+#[serde(default)]
+pub struct Crit {
+    pub chance: f32,
+    pub multiplier: f32,
+}
+
+impl Default for Crit {
+    fn default() -> Self {
+        Self {
+            chance: 0.0,
+            multiplier: 1.5,
+        }
+    }
+}
+
+impl Crit {
+    pub fn with_bonus(self, bonus: &CritBonus) -> Self {
+        Self {
+            chance: self.chance + bonus.chance,
+            multiplier: self.multiplier + bonus.multiplier,
+        }
+    }
+}
+
 #[derive(Component, Serialize, Deserialize, Clone)]
 pub struct DamageOnTouch {
     pub value: f32,
+    #[serde(default)]
+    pub damage_type: DamageType,
+    #[serde(default)]
+    pub crit: Crit,
+    //speed the target gets pushed away from the source with.
+    //only enemy touch damage is loaded from yaml, so that's what the serde default is for
+    #[serde(default = "default_touch_knockback")]
+    pub knockback: f32,
     #[serde(skip)]
     pub count_triggers: u32,
 }
 
+/// Fraction of each damage type that is ignored. Negative values are weaknesses,
+/// eg. `fire: -0.5` takes 50% more fire damage.
+#[derive(Component, Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(transparent)]
+pub struct Resistances {
+    pub values: HashMap<DamageType, f32>,
+}
+
+impl Resistances {
+    pub fn damage_multiplier(&self, damage_type: DamageType) -> f32 {
+        1.0 - self.values.get(&damage_type).copied().unwrap_or(0.0)
+    }
+}
+
+/// Lets a damager hit the same target at most once every `seconds`.
+/// Every damager that deals damage continuously needs one, or it would hit every frame.
+#[derive(Component, Clone, Debug, Default)]
+pub struct HitCooldown {
+    pub seconds: f32,
+    pub timers: HashMap<Entity, Timer>,
+}
+
+impl HitCooldown {
+    pub fn from_seconds(seconds: f32) -> Self {
+        Self {
+            seconds,
+            timers: HashMap::new(),
+        }
+    }
+
+    pub fn is_ready(&self, target: Entity) -> bool {
+        !self.timers.contains_key(&target)
+    }
+
+    pub fn start(&mut self, target: Entity) {
+        self.timers.insert(target, Timer::from_seconds(self.seconds, Once));
+    }
+
+    pub fn tick(&mut self, delta: Duration) {
+        self.timers.retain(|_, timer| !timer.tick(delta).finished());
+    }
+}
+
+/// The player's i-frames. After any hit lands, nothing else can hurt the player until the timer runs out,
+/// so a crowd touching the player on the same frame only hits once.
+#[derive(Component, Clone, Debug)]
+pub struct Invulnerable {
+    pub timer: Timer,
+}
+
+impl Invulnerable {
+    /// Starts out finished, so the first hit always lands.
+    pub fn from_seconds(seconds: f32) -> Self {
+        let mut timer = Timer::from_seconds(seconds, Once);
+        timer.tick(Duration::from_secs_f32(seconds));
+        Self { timer }
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.timer.finished()
+    }
+}
+
 #[derive(Component, Serialize, Deserialize, Clone)]
 pub struct ApplyColdOnTouch {
     pub multiplier: f32,
     pub seconds: f32,
 }
 
+#[derive(Component, Serialize, Deserialize, Clone)]
+pub struct ApplyBurnOnTouch {
+    pub damage_per_tick: f32,
+    pub tick_seconds: f32,
+    pub seconds: f32,
+    pub stacking: StatusStacking,
+}
+
 impl Default for DamageOnTouch {
     fn default() -> Self {
         Self {
             value: 1.0,
+            damage_type: DamageType::Physical,
+            crit: Crit::default(),
+            knockback: 0.0,
             count_triggers: 0,
         }
     }
 }
 
+fn default_touch_knockback() -> f32 {
+    DEFAULT_TOUCH_KNOCKBACK
+}
+
+/// Fraction of knockback that is ignored, 1.0 can't be moved at all.
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, Default)]
+#[serde(transparent)]
+pub struct KnockbackResistance {
+    pub value: f32,
+}
+
+/// Pushed around by a hit. Overrides whatever velocity the entity would normally steer with.
+#[derive(Component, Reflect, Serialize, Deserialize, Clone, Default, TemporaryComponent)]
+pub struct KnockedBack {
+    pub velocity: Vec2,
+    pub timer: Timer,
+}
+
 #[derive(Component)]
 pub struct GainXPOnTouch {
     pub value: u32,
@@ -265,6 +724,23 @@ pub struct FlaskProjectileBundle {
     pub damage: DamageOnTouch,
     pub lifetime: Lifetime,
     pub collisions : CollidingEntities,
+    pub hit_cooldown: HitCooldown,
+    pub burn: ApplyBurnOnTouch,
+}
+
+#[derive(Bundle)]
+pub struct OrbitProjectileBundle {
+    pub sprite_sheet: AnimatedSpriteBundle,
+    //no rigid body, the projectile rides along on its parent's
+    pub collider: Collider,
+    pub collision_layers: CollisionGroups,
+    pub active_events: ActiveEvents,
+    pub name: Name,
+    pub sensor: Sensor,
+    pub damage: DamageOnTouch,
+    pub collisions : CollidingEntities,
+    pub hit_cooldown: HitCooldown,
+    pub orbiting: Orbiting,
 }
 
 #[derive(Component)]
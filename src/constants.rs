@@ -6,6 +6,9 @@ use bevy::prelude::{Color, Val};
 // Using the default 2D camera they correspond 1:1 with screen pixels.
 pub const PADDLE_SIZE: Vec3 = Vec3::new(50.0, 50.0, 1.0);
 pub const PLAYER_SPEED: f32 = 200.0;
+pub const PLAYER_HEALTH: f32 = 100.0;
+pub const PLAYER_MANA: f32 = 100.0;
+pub const PLAYER_MANA_REGEN: f32 = 8.0;
 // How close can the paddle get to the wall
 pub const PADDLE_PADDING: f32 = 10.0;
 
@@ -27,6 +30,33 @@ pub const TEXT_COLOR: Color = Color::rgb(0.5, 0.5, 1.0);
 pub const SCORE_COLOR: Color = Color::rgb(1.0, 0.5, 0.5);
 
 pub const PIXEL_SCALE: f32 = 4.0;
+//abilities at this level aren't offered on level up anymore, and weapons can evolve
+pub const MAX_ABILITY_LEVEL: u8 = 5;
+//how long a hit overrides movement for
+pub const KNOCKBACK_SECONDS: f32 = 0.15;
+//enemies without a knockback in their touch damage push the player this hard
+pub const DEFAULT_TOUCH_KNOCKBACK: f32 = 400.0;
+//how often one enemy can hit the player while touching it
+pub const ENEMY_TOUCH_HIT_COOLDOWN: f32 = 0.5;
+pub const PLAYER_I_FRAMES: f32 = 0.2;
+//radians between the extra projectiles the amount stat adds
+pub const EXTRA_PROJECTILE_SPREAD: f32 = 0.15;
+//how many implants the player can have installed at once
+pub const IMPLANT_SLOTS: usize = 2;
+//what each special round does once fired, see load_special_round
+//subtracted from the enemy's move speed multiplier, 1.0 takes away all of its base speed
+pub const FROZEN_ROUND_COLD_MULTIPLIER: f32 = 1.0;
+pub const FROZEN_ROUND_COLD_SECONDS: f32 = 2.0;
+pub const SPECIAL_ROUND_BURN_DAMAGE_PER_TICK: f32 = 1.0;
+pub const SPECIAL_ROUND_BURN_TICK_SECONDS: f32 = 0.5;
+pub const INCENDIARY_ROUND_BURN_SECONDS: f32 = 3.0;
+pub const EXPLOSIVE_ROUND_RADIUS: f32 = 120.0;
+//of the bullet's own damage
+pub const EXPLOSIVE_ROUND_DAMAGE_MULTIPLIER: f32 = 1.5;
+pub const EXPLOSIVE_ROUND_MIN_MULTIPLIER: f32 = 0.3;
+pub const EXPLOSIVE_ROUND_KNOCKBACK: f32 = 300.0;
+pub const EXPLOSIVE_ROUND_BURN_SECONDS: f32 = 2.0;
+pub const PIERCING_ROUND_EXTRA_PIERCE: u8 = 3;
 pub const STARTING_LAYER: f32 = 100.0;
 pub const BACKGROUND_PROJECTILE_LAYER: f32 = -1.0 + STARTING_LAYER;
 pub const DAMAGE_TEXT_LAYER: f32 = 1.0 + ENEMY_LAYER;
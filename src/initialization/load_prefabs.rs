@@ -4,14 +4,17 @@ use std::fs::{DirEntry, FileType};
 use std::path::PathBuf;
 
 use bevy::asset::{AssetServer, Handle};
-use bevy::prelude::{Bundle, Commands, Res, ResMut, Resource, SpatialBundle};
+use bevy::prelude::{Bundle, Commands, Component, Res, ResMut, Resource, SpatialBundle};
 use bevy_asepritesheet::core::{load_spritesheet, load_spritesheet_then};
 use bevy_asepritesheet::prelude::{AnimEndAction, Spritesheet};
 use serde::Deserialize;
 use serde::Serialize;
 
 use crate::bundles::{EnemyBundle, EnemyData};
-use crate::components::Cooldown;
+use crate::components::{Cooldown, Crit, DamageType, Homing, HomingTarget, SpecialRound, StatusStacking, Targeting};
+use crate::systems::evolution::{EvolutionRecipe, Evolutions};
+use crate::systems::implants::{ImplantData, Implants};
+use crate::systems::synergies::{Synergies, SynergyRule};
 use bevy::log::*;
 
 //on startup, load all images
@@ -38,6 +41,144 @@ pub struct Enemies {
     pub datas: HashMap<String, EnemyBundle>,
 }
 
+#[derive(Resource)]
+pub struct Guns {
+    //keyed by file name, eg. prefabs/guns/fireball.yaml -> fireball
+    pub datas: HashMap<String, GunData>,
+}
+
+impl Guns {
+    pub fn get(&self, id: &GunId) -> &GunData {
+        self.datas
+            .get(&id.0)
+            .unwrap_or_else(|| panic!("gun {} not found!", id.0))
+    }
+}
+
+/// Which entry of the [`Guns`] registry an ability entity reads its stats from.
+#[derive(Component, Clone, Debug, Serialize, Deserialize)]
+pub struct GunId(pub String);
+
+/// A stat that grows linearly with ability level.
+/// Level 1 has the `base` value, every level after that adds `per_level`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LevelCurve {
+    pub base: f32,
+    pub per_level: f32,
+    pub min: Option<f32>,
+    pub max: Option<f32>,
+}
+
+impl LevelCurve {
+    pub fn value_at(&self, level: u8) -> f32 {
+        let mut value = self.base + self.per_level * level.saturating_sub(1) as f32;
+        if let Some(min) = self.min {
+            value = value.max(min);
+        }
+        if let Some(max) = self.max {
+            value = value.min(max);
+        }
+        value
+    }
+}
+
+/// Pressing reload again while the reload is between `window_start` and `window_end`
+/// (fractions of the full reload) finishes it instantly, pressing it anywhere else jams the gun.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ActiveReloadConfig {
+    pub window_start: f32,
+    pub window_end: f32,
+    pub damage_multiplier: f32,
+    pub buff_seconds: f32,
+    pub jam_seconds: f32,
+}
+
+impl ActiveReloadConfig {
+    pub fn in_window(&self, progress: f32) -> bool {
+        progress >= self.window_start && progress <= self.window_end
+    }
+}
+
+/// Projectiles of guns with this steer towards enemies once the gun reaches `from_level`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HomingConfig {
+    #[serde(default)]
+    pub mode: HomingTarget,
+    #[serde(default = "default_homing_from_level")]
+    pub from_level: u8,
+    //radians per second
+    pub turn_rate: LevelCurve,
+    pub range: f32,
+}
+
+fn default_homing_from_level() -> u8 {
+    1
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GunData {
+    //seconds
+    pub cooldown: LevelCurve,
+    //name of the sprite sheet projectiles of this gun use, if it has any
+    #[serde(default)]
+    pub sprite_sheet: String,
+    #[serde(default)]
+    pub damage_type: DamageType,
+    #[serde(default)]
+    pub stats: HashMap<String, LevelCurve>,
+    //guns without this reload the old fashioned way
+    #[serde(default)]
+    pub active_reload: Option<ActiveReloadConfig>,
+    //guns that don't aim, like orbit, leave this out
+    #[serde(default)]
+    pub targeting: Option<Targeting>,
+    #[serde(default)]
+    pub homing: Option<HomingConfig>,
+    //how burns from this gun combine with a burn the enemy already has
+    #[serde(default)]
+    pub burn_stacking: StatusStacking,
+    //rounds this gun hands out, eg. summon munition's overfill
+    #[serde(default)]
+    pub special_rounds: Vec<SpecialRound>,
+}
+
+impl GunData {
+    pub fn cooldown_for_level(&self, level: u8) -> f32 {
+        self.cooldown.value_at(level)
+    }
+
+    pub fn stat(&self, name: &str, level: u8) -> f32 {
+        self.stats
+            .get(name)
+            .unwrap_or_else(|| panic!("gun stat {} not found!", name))
+            .value_at(level)
+    }
+
+    /// For stats most guns don't bother setting.
+    pub fn stat_or(&self, name: &str, level: u8, default: f32) -> f32 {
+        self.stats
+            .get(name)
+            .map_or(default, |curve| curve.value_at(level))
+    }
+
+    pub fn homing_for_level(&self, level: u8) -> Option<Homing> {
+        let config = self.homing.as_ref()?;
+        if level < config.from_level {
+            return None;
+        }
+        Some(Homing::new(config.turn_rate.value_at(level), config.range, config.mode))
+    }
+
+    pub fn crit_for_level(&self, level: u8) -> Crit {
+        let default = Crit::default();
+        Crit {
+            chance: self.stat_or("crit_chance", level, default.chance),
+            multiplier: self.stat_or("crit_multiplier", level, default.multiplier),
+        }
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug)]
 pub struct AtlasLayout {
     pub cols: usize,
@@ -48,6 +189,9 @@ pub struct AtlasLayout {
 
 const GUNS_PATH: &str = "assets\\prefabs\\guns\\";
 const ENEMIES_PATH: &str = "assets\\prefabs\\enemies\\";
+const EVOLUTIONS_PATH: &str = "assets\\prefabs\\evolutions\\";
+const IMPLANTS_PATH: &str = "assets\\prefabs\\implants\\";
+const SYNERGIES_PATH: &str = "assets\\prefabs\\synergies\\";
 const SPRITES_PATH: &str = "assets\\"; //has to be root of assets for now due to bug in spritesheet package
 
 pub fn load_sprites(
@@ -124,21 +268,92 @@ pub fn load_enemy_prefabs(mut enemies: ResMut<Enemies>, atlases: ResMut<Atlases>
     }
 }
 
-pub fn load_gun(gun: usize) -> Cooldown {
+pub fn load_gun_prefabs(mut guns: ResMut<Guns>) {
     let paths: Vec<DirEntry> = fs::read_dir(GUNS_PATH)
         .unwrap()
         .filter_map(|entry| entry.ok())
         .collect();
+    for dir in paths.iter() {
+        let gun_name = dir
+            .path()
+            .with_extension("")
+            .file_name()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_string();
+        info!("Loaded gun {}", dir.path().display());
+        guns.datas.insert(
+            gun_name,
+            load_data_from_path::<GunData>(dir.path().to_str().unwrap()),
+        );
+    }
+}
+
+pub fn load_evolution_prefabs(mut evolutions: ResMut<Evolutions>) {
+    let paths: Vec<DirEntry> = fs::read_dir(EVOLUTIONS_PATH)
+        .unwrap()
+        .filter_map(|entry| entry.ok())
+        .collect();
+    for dir in paths.iter() {
+        let recipe_name = dir
+            .path()
+            .with_extension("")
+            .file_name()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_string();
+        info!("Loaded evolution {}", dir.path().display());
+        evolutions.recipes.insert(
+            recipe_name,
+            load_data_from_path::<EvolutionRecipe>(dir.path().to_str().unwrap()),
+        );
+    }
+}
 
-    let paths_count = paths.len();
-    let path = &paths[gun % paths_count];
-    let file_path = path.path();
-    info!("Loaded gun {}", file_path.display());
-    let gun_yaml = fs::read_to_string(file_path).expect("failed to load yaml!");
-    let gun =
-        serde_yaml::from_str::<Cooldown>(gun_yaml.as_str()).expect("failed to deserialize gun!");
+pub fn load_implant_prefabs(mut implants: ResMut<Implants>) {
+    let paths: Vec<DirEntry> = fs::read_dir(IMPLANTS_PATH)
+        .unwrap()
+        .filter_map(|entry| entry.ok())
+        .collect();
+    for dir in paths.iter() {
+        let implant_name = dir
+            .path()
+            .with_extension("")
+            .file_name()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_string();
+        info!("Loaded implant {}", dir.path().display());
+        implants.datas.insert(
+            implant_name,
+            load_data_from_path::<ImplantData>(dir.path().to_str().unwrap()),
+        );
+    }
+}
 
-    gun
+pub fn load_synergy_prefabs(mut synergies: ResMut<Synergies>) {
+    let paths: Vec<DirEntry> = fs::read_dir(SYNERGIES_PATH)
+        .unwrap()
+        .filter_map(|entry| entry.ok())
+        .collect();
+    for dir in paths.iter() {
+        let synergy_name = dir
+            .path()
+            .with_extension("")
+            .file_name()
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_string();
+        info!("Loaded synergy {}", dir.path().display());
+        synergies.rules.insert(
+            synergy_name,
+            load_data_from_path::<SynergyRule>(dir.path().to_str().unwrap()),
+        );
+    }
 }
 
 pub fn _save_enemy(bundle: EnemyData) {
@@ -172,7 +387,3 @@ pub fn load_data_from_path<T: for<'a> Deserialize<'a>>(path: &str) -> T {
         .unwrap_or_else(|_| panic!("failed to deserialize data at path {}!", path));
     enemy
 }
-
-pub fn load_gun_test() {
-    // load_gun(999);
-}
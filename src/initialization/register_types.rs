@@ -1,13 +1,17 @@
 use bevy::app::App;
 
 use crate::animation::AnimatorController;
-use crate::components::{PassiveXPMultiplier, XPMultiplier, XP};
+use crate::components::{CritBonus, Mana, ManaCost, PassiveCritBonus, PassiveXPMultiplier, Targeting, XP};
 
 pub(crate) fn register_types(app: &mut App) -> &mut App {
     app.register_type::<AnimatorController>();
     app.register_type::<XP>();
-    app.register_type::<XPMultiplier>();
     app.register_type::<PassiveXPMultiplier>();
+    app.register_type::<CritBonus>();
+    app.register_type::<PassiveCritBonus>();
+    app.register_type::<Targeting>();
+    app.register_type::<Mana>();
+    app.register_type::<ManaCost>();
 
     app
 }
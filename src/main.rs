@@ -1,6 +1,6 @@
 #![feature(duration_constructors)]
 
-use crate::guns::ShootEvent;
+use crate::guns::{ActiveReloadEvent, ShootEvent};
 use crate::physics::walls::WallBundle;
 use bevy_rapier2d::prelude::{CollidingEntities, PhysicsSet, RapierDebugRenderPlugin};
 use std::collections::HashMap;
@@ -39,16 +39,19 @@ use constants::BACKGROUND_COLOR;
 use crate::bundles::{
     CorpseSpawnData, EnemySpawnData, Object, PlayerBundle, PlayerSpawn, XPSpawnData,
 };
-use crate::components::{Cold, Dashing};
 use crate::initialization::inspector::add_inspector;
-use crate::initialization::load_prefabs::{Atlases, Enemies};
+use crate::initialization::load_prefabs::{Atlases, Enemies, Guns};
 use crate::physics::walls::Wall;
 use crate::systems::guns::{
-    DamageTextSpawnData, Damaged, FireballSpawnData, FlaskSpawnData, IceballSpawnData,
-    ParticleSpawnData, PistolBulletSpawnData,
+    DamageTextSpawnData, Damaged, FlaskSpawnData, OrbitSpawnData, ParticleSpawnData, ProjectileSpawnData,
 };
 use crate::{initialization::register_types::register_types, systems::*};
+use crate::systems::dev::DebugDrawing;
+use crate::systems::evolution::{EvolveEvent, Evolutions};
+use crate::systems::implants::{Implants, InstallImplantEvent};
+use crate::systems::loadout::LearnAbilityEvent;
 use crate::systems::stats::DeathEvent;
+use crate::systems::synergies::{ActiveSynergies, Synergies};
 
 mod components;
 
@@ -78,7 +81,6 @@ fn main() {
     setup_logging();
     //TODO:
     //make abilities triggered?_
-    //toy with reload mechanics?
     //add better ui for cooldowns and stuff?
     //replace ice spike sprite
     //replace molotov sprite
@@ -129,13 +131,13 @@ fn main() {
             EntropyPlugin::<WyRand>::default(),
             EguiPlugin,
             LdtkPlugin,
+            status_effects::StatusEffectPlugin,
         ))
         .add_plugins((
             SpewPlugin::<Object, EnemySpawnData>::default(),
-            SpewPlugin::<Object, FireballSpawnData>::default(),
-            SpewPlugin::<Object, PistolBulletSpawnData>::default(),
-            SpewPlugin::<Object, IceballSpawnData>::default(),
+            SpewPlugin::<Object, ProjectileSpawnData>::default(),
             SpewPlugin::<Object, FlaskSpawnData>::default(),
+            SpewPlugin::<Object, OrbitSpawnData>::default(),
             SpewPlugin::<Object, DamageTextSpawnData>::default(),
             SpewPlugin::<Object, CorpseSpawnData>::default(),
             SpewPlugin::<Object, XPSpawnData>::default(),
@@ -149,6 +151,20 @@ fn main() {
         .insert_resource(Enemies {
             datas: HashMap::new(),
         })
+        .insert_resource(Guns {
+            datas: HashMap::new(),
+        })
+        .insert_resource(Evolutions {
+            recipes: HashMap::new(),
+        })
+        .insert_resource(Implants {
+            datas: HashMap::new(),
+        })
+        .insert_resource(Synergies {
+            rules: HashMap::new(),
+        })
+        .insert_resource(ActiveSynergies::default())
+        .insert_resource(DebugDrawing::default())
         .insert_resource(SpriteAnimController::default())
         .insert_resource(LevelSelection::index(1))
         .insert_resource(spawning::RoundTimer {
@@ -158,10 +174,9 @@ fn main() {
         .add_event::<CollisionEvent>()
         .register_ldtk_int_cell_for_layer::<WallBundle>("Walls", 1)
         .add_spawner((Object::Enemy, bundles::spawn_enemy))
-        .add_spawner((Object::Fireball, guns::spawn_fireball))
-        .add_spawner((Object::PistolBullet, guns::spawn_pistol_bullet))
-        .add_spawner((Object::Iceball, guns::spawn_iceball))
+        .add_spawner((Object::Projectile, guns::spawn_projectile))
         .add_spawner((Object::Flask, guns::spawn_flask_projectile))
+        .add_spawner((Object::Orbit, guns::spawn_orbit_projectile))
         .add_spawner((Object::DamageNumber, guns::spawn_damage_text))
         .add_spawner((Object::Corpse, bundles::spawn_corpse))
         .add_spawner((Object::XP, bundles::spawn_xp))
@@ -174,8 +189,11 @@ fn main() {
             (
                 initialization::load_prefabs::load_sprites,
                 initialization::load_prefabs::load_enemy_prefabs,
+                initialization::load_prefabs::load_gun_prefabs,
+                initialization::load_prefabs::load_evolution_prefabs,
+                initialization::load_prefabs::load_implant_prefabs,
+                initialization::load_prefabs::load_synergy_prefabs,
                 setup::setup,
-                initialization::load_prefabs::load_gun_test,
             )
                 .chain(),
         )
@@ -186,14 +204,16 @@ fn main() {
             (
                 guns::expire_entities,
                 guns::expire_bullets_on_hit,
-                guns::expired_bullets_explode,
+                (guns::expired_bullets_explode, guns::explode_expired_projectiles),
                 spawning::enemy_spawn_cycle,
                 //abilities
                 guns::advance_cooldowns,
                 guns::fireball_gun,
                 guns::pistol_gun,
+                guns::shotgun_gun,
+                guns::sniper_gun,
                 guns::iceball_gun,
-                guns::flask_weapon,
+                (guns::flask_weapon, guns::orbit_weapon, guns::chain_lightning_gun, guns::summon_munition),
                 // audio::play_collision_sound,
                 //stats
 
@@ -210,10 +230,26 @@ fn main() {
                 // `chain`ing systems together runs them in order
                 .chain(),
         )
+        .add_systems(
+            FixedUpdate,
+            stats::burning_enemies_spawn_particles
+                .after(stats::cold_enemies_spawn_particles)
+                .run_if(in_state(AppState::InGame)),
+        )
+        .add_systems(
+            FixedUpdate,
+            guns::steer_homing_projectiles
+                .after(guns::iceball_gun)
+                .run_if(in_state(AppState::InGame)),
+        )
         .add_systems(PreUpdate, (spawning::set_level_bounds))
         .insert_resource(input::AimDirection(Vec2::ZERO))
         .add_event::<DeathEvent>()
         .add_event::<ShootEvent>()
+        .add_event::<ActiveReloadEvent>()
+        .add_event::<EvolveEvent>()
+        .add_event::<InstallImplantEvent>()
+        .add_event::<LearnAbilityEvent>()
         .add_systems(
             Update,
             (
@@ -225,13 +261,24 @@ fn main() {
         .add_systems(
             Update,
             (ui::update_player_health_ui,
-             ui::show_bullets, ),
+             ui::show_bullets,
+             ui::show_mana,
+             ui::targeting_window,
+             ui::loadout_panel,
+             targeting::init_targeting_from_gun_data,
+             evolution::evolve_weapons,
+             implants::install_implants,
+             loadout::learn_abilities, ),
         )
         .add_systems(
             Update,
-            (guns::process_temporary_component::<Damaged>,
-             guns::process_temporary_component::<Cold>,
-             guns::process_temporary_component::<Dashing>, )
+            (
+                guns::apply_burn_on_collide,
+                guns::apply_burn_on_collide_start,
+                guns::burn_damage_tick,
+                guns::move_orbiting_projectiles,
+                guns::tick_hit_cooldowns.before(guns::deal_damage_on_collide),
+            )
                 .run_if(in_state(AppState::InGame)),
         )
     .add_systems(
@@ -243,24 +290,38 @@ fn main() {
             physics::walls::spawn_wall_collision,
             spawning::move_player_to_spawn_point,
             (
-                stats::update_move_speed_from_passive,
                 movement::apply_move_speed_multiplier,
                 movement::move_player,
                 movement::set_follower_velocity,
+                movement::apply_knockback,
                 stats::move_speed_mod_affects_animation_speed,
             )
                 .chain(),
             // movement::_debug_collisions,
-            guns::reload_gun_system,
+            (guns::reload_gun_system, guns::active_reload_system).chain(),
+            guns::apply_damage_falloff
+                .before(guns::deal_damage_on_collide)
+                .before(guns::deal_damage_on_collide_start),
             guns::deal_damage_on_collide,
             guns::deal_damage_on_collide_start,
+            guns::ricochet_bullets.after(guns::deal_damage_on_collide_start),
             guns::apply_cold_on_collide,
             guns::apply_cold_on_collide_start,
             ((stats::snowball_reload_bullet_if_killed_enemy_is_frozen).before(stats::destroy_dead)
             .after(guns::apply_cold_on_collide)
             .after(guns::apply_cold_on_collide_start)),
-            movement::apply_xp_radius,
-            movement::apply_xp_multiplier,
+            stats::queue_special_rounds_on_kill,
+            (
+                movement::apply_xp_radius,
+                movement::apply_crit_bonus,
+                stats::regenerate_mana,
+                (
+                    (modifiers::apply_levelled_modifiers, loadout::apply_tome),
+                    modifiers::resolve_player_stats,
+                    (modifiers::apply_max_health, modifiers::apply_max_mana, synergies::resolve_ability_stats),
+                )
+                    .chain(),
+            ),
             stats::pick_up_xp_on_touch,
             stats::vacuum_xp_on_touch,
             stats::level_up,
@@ -268,6 +329,7 @@ fn main() {
             (
                 stats::reset_sprite_color,
                 stats::cold_objects_are_blue,
+                stats::burning_objects_are_red,
                 stats::highlight_damaged,
             )
                 .chain(),
@@ -277,15 +339,24 @@ fn main() {
     )
         .add_systems(
             Update,
-            (
-                stats::update_level_descriptions_xp_multiplier,
+            ((
+                stats::update_level_descriptions_modifiers,
                 stats::update_level_descriptions_xp_radius,
                 stats::update_level_descriptions_flask,
                 stats::update_level_descriptions_fireball,
                 stats::update_level_descriptions_pistol,
-                stats::update_level_descriptions_move_speed,
                 stats::update_level_descriptions_iceball,
+                stats::update_level_descriptions_shotgun,
+                stats::update_level_descriptions_sniper,
+                stats::update_level_descriptions_crit_bonus,
+                stats::update_level_descriptions_orbit,
+                stats::update_level_descriptions_chain_lightning,
+                stats::update_level_descriptions_tome,
+                stats::update_level_descriptions_summon_munition,
             ),
+            stats::update_level_descriptions_evolved,
+            )
+                .chain(),
         )
         .add_systems(
             Update,
@@ -293,6 +364,7 @@ fn main() {
                 //Always update loop
                 bevy::window::close_on_esc,
                 dev::log_transitions,
+                dev::draw_debug_circles.run_if(dev::debug_drawing_enabled),
             ),
         )
         .add_systems(
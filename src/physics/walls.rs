@@ -10,6 +10,10 @@ use std::collections::{HashMap, HashSet};
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Component)]
 pub struct Wall;
 
+//the merged collider spawned for a group of wall tiles
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Component)]
+pub struct WallCollider;
+
 #[derive(Clone, Debug, Default, Bundle, LdtkIntCell)]
 pub struct WallBundle {
     wall: Wall,
@@ -178,6 +182,7 @@ pub fn spawn_wall_collision(
                                 0.,
                             ))
                             .insert(CollisionGroups::new(GROUND, Group::ALL))
+                            .insert(WallCollider)
                             .insert(GlobalTransform::default());
                     }
                 });
@@ -1,8 +1,9 @@
-use crate::components::{AbilityLevel, Ammo, AttackSpeed, Cooldown, DashAbility, FireBallGun, Flask, IceBallGun, ParentMoveSpeedMultiplier, PassiveMoveSpeedMultiplier, PassiveXPMultiplier, PistolGun, Player, Reloadable, XPPickupRadius, XPVacuum};
+use crate::components::{AbilityLevel, DashAbility, Player, XPPickupRadius, XPVacuum};
 use crate::constants::{
     PIXEL_SCALE, SCOREBOARD_FONT_SIZE, SCOREBOARD_TEXT_PADDING, SCORE_COLOR, TEXT_COLOR,
 };
 use crate::physics::layers::game_layer;
+use crate::systems::loadout::{AbilityKind, LearnAbilityEvent};
 use crate::*;
 use bevy::math::vec3;
 use bevy_ecs_ldtk::LdtkWorldBundle;
@@ -12,7 +13,7 @@ use bevy_rapier2d::prelude::CollisionGroups;
 
 // Add the game's entities to our world
 // #[bevycheck::system]
-pub fn setup(mut commands: Commands, atlases: ResMut<Atlases>, asset_server: Res<AssetServer>, mut contexts: EguiContexts) {
+pub fn setup(mut commands: Commands, atlases: ResMut<Atlases>, asset_server: Res<AssetServer>, mut contexts: EguiContexts, mut learn_events: EventWriter<LearnAbilityEvent>) {
     // Camera
     let camera = commands.spawn(Camera2dBundle::default());
     egui_extras::install_image_loaders(contexts.ctx());
@@ -23,6 +24,10 @@ pub fn setup(mut commands: Commands, atlases: ResMut<Atlases>, asset_server: Res
     // Get the specific entity you want
 
     spawn_player(&mut commands, atlases, Vec2::ZERO);
+    //starting weapon
+    learn_events.send(LearnAbilityEvent {
+        ability: AbilityKind::Pistol,
+    });
 
     commands.spawn(LdtkWorldBundle {
         ldtk_handle: asset_server.load("levels/cemetery-0/cemetery-0.ldtk"),
@@ -91,81 +96,14 @@ fn spawn_player(commands: &mut Commands, atlases: ResMut<Atlases>, position: Vec
                     ..default()
                 },
             ));
-            //fireball gun
-            parent.spawn((
-                Cooldown::with_cooldown(500),
-                FireBallGun {},
-                Name::new("Fireball"),
-                AbilityLevel {
-                    level: 0,
-                    ..default()
-                },
-                SpatialBundle { ..default() },
-            ));
-            //pistol
-            parent.spawn((
-                Cooldown::with_cooldown(1000),
-                PistolGun {},
-                Name::new("Pistol"),
-                AbilityLevel {
-                    level: 1,
-                    ..default()
-                },
-                SpatialBundle { ..default() },
-                Ammo{
-                    max_amount: 6,
-                },
-                Reloadable{ reload_seconds_per_bullet: 0.25_f32 }
-            ));
-            //iceball gun
-            parent.spawn((
-                Name::new("Snowball"),
-                Cooldown::with_cooldown(900),
-                IceBallGun {},
-                AbilityLevel {
-                    level: 0,
-                    ..default()
-                },
-                SpatialBundle { ..default() },
-            ));
-            // flask gun
-            parent.spawn((
-                Name::new("Molotov"),
-                Cooldown::with_cooldown(0),
-                Flask {},
-                AbilityLevel {
-                    level: 0,
-                    ..default()
-                },
-                SpatialBundle { ..default() },
-            ));
-            //move speed ability
-            parent.spawn((
-                Name::new("Move Speed"),
-                PassiveMoveSpeedMultiplier { ..default() },
-                ParentMoveSpeedMultiplier { value: 0.0 },
-                AbilityLevel {
-                    level: 0,
-                    ..default()
-                },
-                // SpatialBundle { ..default() },
-            ));
-            parent.spawn((
-                Name::new("XP Bonus"),
-                PassiveXPMultiplier {},
-                AbilityLevel {
-                    level: 0,
-                    ..default()
-                },
-                // SpatialBundle { ..default() },
-            ));
             //xp gatherer
             parent.spawn((
                 Name::new("XP Pickup Radius"),
                 XPPickupRadius { radius: 0.0 },
                 XPVacuum {},
+                //everyone starts with a small pickup radius, it doesn't take a slot
                 AbilityLevel {
-                    level: 0,
+                    level: 1,
                     ..default()
                 },
                 Collider::ball(50.0),
@@ -177,7 +115,6 @@ fn spawn_player(commands: &mut Commands, atlases: ResMut<Atlases>, position: Vec
                 Sensor,
                 ActiveEvents::COLLISION_EVENTS,
             ));
-            parent.spawn((AttackSpeed { percent: 0.0 },));
         });
 }
 
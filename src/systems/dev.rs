@@ -1,6 +1,7 @@
 use crate::AppState;
 use bevy::log::info;
-use bevy::prelude::{EventReader, StateTransitionEvent};
+use bevy::math::Vec3Swizzles;
+use bevy::prelude::{Color, Component, EventReader, Gizmos, GlobalTransform, Query, Res, Resource, StateTransitionEvent};
 
 /// print when an `AppState` transition happens
 /// also serves as an example of how to use `StateTransitionEvent`
@@ -12,3 +13,26 @@ pub fn log_transitions(mut transitions: EventReader<StateTransitionEvent<AppStat
         );
     }
 }
+
+/// Debug gizmos like [`DebugCircle`] are only drawn while this is on, off by default.
+#[derive(Resource, Default)]
+pub struct DebugDrawing {
+    pub enabled: bool,
+}
+
+pub fn debug_drawing_enabled(debug: Res<DebugDrawing>) -> bool {
+    debug.enabled
+}
+
+/// Outline drawn for as long as the entity lives, eg. the blast radius of an explosion.
+#[derive(Component)]
+pub struct DebugCircle {
+    pub radius: f32,
+    pub color: Color,
+}
+
+pub fn draw_debug_circles(mut gizmos: Gizmos, circles: Query<(&DebugCircle, &GlobalTransform)>) {
+    for (circle, transform) in circles.iter() {
+        gizmos.circle_2d(transform.translation().xy(), circle.radius, circle.color);
+    }
+}
@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::components::{AbilityLevel, SpecialAmmoQueue, Targeting};
+use crate::constants::MAX_ABILITY_LEVEL;
+use crate::initialization::load_prefabs::GunId;
+use crate::systems::loadout::{spawn_ability, AbilityKind};
+
+/// A max level weapon plus a passive turns into a new weapon.
+/// Weapon and passive are matched by the [`Name`] of their ability entity.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EvolutionRecipe {
+    pub name: String,
+    pub description: String,
+    pub weapon: String,
+    pub passive: String,
+    //level the passive needs to be at, any level at all by default
+    #[serde(default = "default_passive_level")]
+    pub passive_level: u8,
+    //entry in the guns registry the evolved weapon reads its stats from
+    pub evolved_gun: String,
+}
+
+fn default_passive_level() -> u8 {
+    1
+}
+
+#[derive(Resource)]
+pub struct Evolutions {
+    //keyed by file name, eg. prefabs/evolutions/hellfire.yaml -> hellfire
+    pub recipes: HashMap<String, EvolutionRecipe>,
+}
+
+impl Evolutions {
+    pub fn get(&self, recipe: &str) -> &EvolutionRecipe {
+        self.recipes
+            .get(recipe)
+            .unwrap_or_else(|| panic!("evolution {} not found!", recipe))
+    }
+
+    /// Recipes that are ready to evolve, paired with the weapon entity they replace.
+    pub fn available(&self, abilities: &[(Entity, &AbilityLevel, &Name)]) -> Vec<(Entity, String)> {
+        let mut available = vec![];
+        for (recipe_name, recipe) in self.recipes.iter() {
+            let weapon = abilities.iter().find(|(_, level, name)| {
+                name.as_str() == recipe.weapon && level.level >= MAX_ABILITY_LEVEL
+            });
+            let has_passive = abilities.iter().any(|(_, level, name)| {
+                name.as_str() == recipe.passive && level.level >= recipe.passive_level
+            });
+            if let (Some((weapon, _, _)), true) = (weapon, has_passive) {
+                available.push((*weapon, recipe_name.clone()));
+            }
+        }
+        available
+    }
+}
+
+/// Marks an ability that came out of an evolution, so its description can use the recipe.
+#[derive(Component, Clone, Debug)]
+pub struct Evolved {
+    pub recipe: String,
+}
+
+#[derive(Event)]
+pub struct EvolveEvent {
+    pub base: Entity,
+    pub recipe: String,
+}
+
+pub fn evolve_weapons(
+    mut events: EventReader<EvolveEvent>,
+    evolutions: Res<Evolutions>,
+    bases: Query<(&Parent, Option<&AbilityKind>, Option<&Targeting>)>,
+    mut special_ammo: Query<&mut SpecialAmmoQueue>,
+    mut commands: Commands,
+) {
+    for event in events.read() {
+        let Ok((parent, kind, targeting)) = bases.get(event.base) else {
+            continue;
+        };
+        let recipe = evolutions.get(&event.recipe);
+        let Some(kind) = kind else {
+            warn!("{} has no ability kind, can't evolve it into {}", recipe.weapon, recipe.name);
+            continue;
+        };
+        info!("Evolving {} into {}", recipe.weapon, recipe.name);
+
+        //the evolved weapon is spawned just like the base, so it keeps every marker and a full magazine,
+        //it just reads its stats from a different gun.
+        //keeping the kind means it still takes up the base weapon's slot, and keeps the base from being offered again
+        let evolved = spawn_ability(&mut commands, parent.get(), *kind, &mut special_ammo);
+        let mut evolved = commands.entity(evolved);
+        evolved.insert((
+            Name::new(recipe.name.clone()),
+            GunId(recipe.evolved_gun.clone()),
+            Evolved {
+                recipe: event.recipe.clone(),
+            },
+        ));
+        //whatever the player picked for the base sticks around
+        if let Some(targeting) = targeting {
+            evolved.insert(*targeting);
+        }
+
+        commands.entity(event.base).despawn_recursive();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deadeye_needs_a_max_level_pistol_and_levelled_precision() {
+        let deadeye: EvolutionRecipe =
+            serde_yaml::from_str(include_str!("../../assets/prefabs/evolutions/deadeye.yaml")).unwrap();
+        let evolutions = Evolutions {
+            recipes: HashMap::from([("deadeye".to_string(), deadeye)]),
+        };
+        let pistol = Entity::from_raw(1);
+        let (pistol_name, precision_name) = (Name::new("Pistol"), Name::new("Precision"));
+        let max_level = AbilityLevel { level: MAX_ABILITY_LEVEL, ..default() };
+        let below_max = AbilityLevel { level: MAX_ABILITY_LEVEL - 1, ..default() };
+        let first_level = AbilityLevel { level: 1, ..default() };
+        let second_level = AbilityLevel { level: 2, ..default() };
+
+        let ready = [(pistol, &max_level, &pistol_name), (Entity::from_raw(2), &second_level, &precision_name)];
+        assert_eq!(evolutions.available(&ready), vec![(pistol, "deadeye".to_string())]);
+
+        let pistol_too_low = [(pistol, &below_max, &pistol_name), (Entity::from_raw(2), &second_level, &precision_name)];
+        assert!(evolutions.available(&pistol_too_low).is_empty());
+
+        //the recipe asks for precision at level 2
+        let precision_too_low = [(pistol, &max_level, &pistol_name), (Entity::from_raw(2), &first_level, &precision_name)];
+        assert!(evolutions.available(&precision_too_low).is_empty());
+
+        let no_precision = [(pistol, &max_level, &pistol_name)];
+        assert!(evolutions.available(&no_precision).is_empty());
+    }
+}
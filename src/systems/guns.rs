@@ -1,37 +1,46 @@
 use bevy::asset::{Assets, Handle};
-use bevy::ecs::query::QueryEntityError;
+use bevy::ecs::query::{Has, QueryEntityError};
 use bevy::log::*;
 
 use bevy::log::tracing_subscriber::fmt::time;
 use bevy::math::{Vec3, Vec3Swizzles};
-use bevy::prelude::{default, BuildChildren, Color, Commands, Component, Entity, EventReader, GlobalTransform, In, Mut, Query, Res, ResMut, Sprite, SpriteSheetBundle, Text, Text2dBundle, TextStyle, Time, Transform, Vec2, With, Without, Event};
+use bevy::ecs::system::EntityCommands;
+use bevy::prelude::{default, BuildChildren, DespawnRecursiveExt, Color, Commands, Component, Entity, EventReader, EventWriter, GlobalTransform, In, Mut, ParamSet, Query, Res, ResMut, SpatialBundle, Sprite, SpriteSheetBundle, Text, Text2dBundle, TextStyle, Time, Transform, Vec2, With, Without, Event};
 use bevy::time::TimerMode::Once;
 use bevy::time::{Timer, TimerMode};
 use bevy_asepritesheet::animator::{AnimFinishEvent, AnimatedSpriteBundle, SpriteAnimator};
 use bevy_asepritesheet::prelude::{AnimEventSender, AnimHandle, Spritesheet};
 use bevy_rapier2d::dynamics::RigidBody;
-use bevy_rapier2d::geometry::{Collider, CollisionGroups, Restitution};
+use bevy_rapier2d::geometry::{ActiveEvents, Collider, CollisionGroups, Restitution};
 use bevy_rapier2d::na::clamp;
 use bevy_rapier2d::parry::math::DEFAULT_EPSILON;
 use bevy_rapier2d::pipeline::CollisionEvent;
 use bevy_rapier2d::plugin::RapierContext;
 use bevy_rapier2d::prelude::{CollidingEntities, QueryFilter, Velocity};
+use bevy_prng::WyRand;
+use bevy_rand::prelude::GlobalEntropy;
 use rand::Rng;
+use rand_core::RngCore;
 use std::time::Duration;
-use bevy::hierarchy::Children;
+use bevy::hierarchy::{Children, Parent};
 use bevy::utils::hashbrown::HashSet;
 use temporary_component_derive::*;
 
 use crate::bundles::{DestroyAfterDeathAnimation, Object, PhysicalBundle};
-use crate::components::{AbilityLevel, Ammo, ApplyColdOnTouch, AttackSpeed, Bullet, BulletBundle, Chambered, Cold, Cooldown, DamageOnTouch, Enemy, Expired, FireBallGun, Flask, FlaskProjectileBundle, Health, IceBallGun, Lifetime, MoveSpeed, PistolGun, Reloadable, Reloading, TemporaryComponent};
-use crate::constants::{BACKGROUND_PROJECTILE_LAYER, DAMAGE_TEXT_LAYER, PIXEL_SCALE};
+use crate::components::{AbilityLevel, ActiveReloadBuff, Ammo, ApplyBurnOnTouch, ApplyColdOnTouch, Bullet, BulletBundle, Burning, ChainLightningGun, Chambered, Cold, Cooldown, Crit, CritBonus, DamageFalloff, DamageOnTouch, DamageType, Enemy, ExplodeOnExpire, ExplosionStatus, Expired, FireBallGun, Flask, FlaskProjectileBundle, Health, HitCooldown, Homing, HomingTarget, IceBallGun, Invulnerable, Jammed, KnockedBack, Lifetime, Mana, ManaCost, MoveSpeed, LoadsSpecialRounds, OrbitData, OrbitGun, OrbitProjectileBundle, Orbiting, PistolGun, Player, Reloadable, Reloading, Resistances, Ricochet, ShotgunGun, SniperGun, SpecialAmmoQueue, SpecialRound, StatusStacking, SummonMunition, SummonMunitionData, Targeting, TemporaryComponent};
+use crate::constants::{BACKGROUND_PROJECTILE_LAYER, DAMAGE_TEXT_LAYER, EXPLOSIVE_ROUND_BURN_SECONDS, EXPLOSIVE_ROUND_DAMAGE_MULTIPLIER, EXPLOSIVE_ROUND_KNOCKBACK, EXPLOSIVE_ROUND_MIN_MULTIPLIER, EXPLOSIVE_ROUND_RADIUS, EXTRA_PROJECTILE_SPREAD, FROZEN_ROUND_COLD_MULTIPLIER, FROZEN_ROUND_COLD_SECONDS, INCENDIARY_ROUND_BURN_SECONDS, KNOCKBACK_SECONDS, PIERCING_ROUND_EXTRA_PIERCE, PIXEL_SCALE, SPECIAL_ROUND_BURN_DAMAGE_PER_TICK, SPECIAL_ROUND_BURN_TICK_SECONDS};
 use crate::extensions::spew_extensions::{Spawn, Spawner};
 use crate::extensions::vectors::to_vec2;
-use crate::initialization::load_prefabs::Atlases;
+use crate::initialization::load_prefabs::{Atlases, GunData, GunId, Guns};
 use crate::physics::layers::game_layer;
+use crate::physics::walls::WallCollider;
+use crate::systems::dev::DebugCircle;
 use crate::systems::ui::FadeTextWithLifetime;
 use crate::Name;
 use crate::systems::input::AimDirection;
+use crate::systems::modifiers::{crit_with_stats, AbilityStats, PlayerStats, Stat};
+use crate::systems::status_effects::ApplyStatusEvent;
+use crate::systems::targeting::TargetFinder;
 
 //adding a new ability
 //1.. add system that does the ability thing. It should require an AbilityLevel component
@@ -39,18 +48,21 @@ use crate::systems::input::AimDirection;
 //3.. add system for updating the description of the ability when its level changes
 //4.. add all 4 systems to update loop.
 pub fn advance_cooldowns(
-    mut query: Query<&mut Cooldown>,
-    mut cdr_query: Query<&mut AttackSpeed>,
+    mut query: Query<(&mut Cooldown, Option<&AbilityStats>, Option<&ManaCost>)>,
+    player: Query<(&PlayerStats, Option<&Mana>), With<Player>>,
     time: Res<Time>,
 ) {
     //assumes only player needs concept of abilities and CDR, which might change.
-    for mut ability in query.iter_mut() {
-        let mut total_cdr = 100.0f32;
-        for cdr in cdr_query.iter_mut() {
-            total_cdr += cdr.percent;
-        }
-
-        let multiplier = total_cdr * 0.01f32; //convert percentage to multiplier
+    let player_cooldown = player.get_single().map_or(1.0, |(stats, _)| stats.get(Stat::Cooldown));
+    let mana = player
+        .get_single()
+        .ok()
+        .and_then(|(_, mana)| mana)
+        .map_or(0.0, |mana| mana.value);
+    for (mut ability, ability_stats, cost) in query.iter_mut() {
+        //lower cooldown stat means the timers run faster
+        let cooldown = ability_stats.map_or(player_cooldown, |ability_stats| ability_stats.stats.get(Stat::Cooldown));
+        let multiplier = 1.0 / cooldown;
         let delta_seconds = time.delta().as_secs_f32();
         let multiplied_delta = delta_seconds * multiplier;
 
@@ -58,32 +70,55 @@ pub fn advance_cooldowns(
         //this idea of advancing the timer will make less sense if we
         //display the timer for the user. If that happens, we will have to
         //track the timer duration and update it based on stats when they change.
+        //spells hold at the end of their cooldown until there's mana to cast them
+        if cost.is_some_and(|cost| mana < cost.amount) && ability.timer.remaining() <= duration {
+            continue;
+        }
         ability.timer.tick(duration);
     }
 }
 
+/// Pays for a spell, abilities without a cost are always free.
+fn try_spend_mana(mana: &mut Query<&mut Mana, With<Player>>, cost: Option<&ManaCost>) -> bool {
+    let Some(cost) = cost else {
+        return true;
+    };
+    let Ok(mut mana) = mana.get_single_mut() else {
+        return false;
+    };
+    //advance_cooldowns already waits for mana, this only fails when two spells finish on the same frame
+    if mana.value < cost.amount {
+        return false;
+    }
+    mana.value -= cost.amount;
+    true
+}
+
 pub fn flask_weapon(
-    mut query: Query<(&mut Cooldown, &GlobalTransform, &Flask, &AbilityLevel)>,
+    mut query: Query<(&mut Cooldown, &GlobalTransform, &Flask, &AbilityLevel, &GunId, &Targeting, &AbilityStats, Option<&ManaCost>)>,
     mut spawner: Spawner<FlaskSpawnData>,
+    target_finder: TargetFinder,
+    mut rng: ResMut<GlobalEntropy<WyRand>>,
+    guns: Res<Guns>,
+    mut mana: Query<&mut Mana, With<Player>>,
 ) {
-    for (ability, transform, flask, level) in query.iter_mut() {
+    for (ability, transform, flask, level, gun_id, targeting, stats, cost) in query.iter_mut() {
         if level.level == 0 {
             continue;
         }
         if ability.timer.just_finished() {
             let translation = transform.translation();
+            //the flask lands right on the target
+            let Some(target) = target_finder.find(targeting, translation.xy(), &mut rng) else {
+                continue;
+            };
+            if !try_spend_mana(&mut mana, cost) {
+                continue;
+            }
 
-            let mut rng = rand::thread_rng();
-            let value = rng.gen_range(0.0..1.0);
-            let angle = value * 2.0 * std::f32::consts::PI;
-            // Calculate the direction vector from the angle
-            let mut direction = Vec2::new(angle.cos(), angle.sin());
-
-            let distance = Vec2::splat(rng.gen_range(50.0..400.0));
-            direction *= distance;
-
-            let mut spawn_data = FlaskSpawnData::get_data_for_level(level.level);
-            spawn_data.position = translation.xy() + direction;
+            let mut spawn_data = FlaskSpawnData::from_gun_data(guns.get(gun_id), level.level);
+            spawn_data.position = target;
+            spawn_data.stats = stats.stats.clone();
             spawner.spawn(Object::Flask, spawn_data);
             // spawn_flask_projectile(&mut commands, flask, direction, &atlases);
         }
@@ -91,140 +126,700 @@ pub fn flask_weapon(
 }
 
 pub fn iceball_gun(
-    mut query: Query<(&mut Cooldown, &GlobalTransform, &IceBallGun, &AbilityLevel)>,
-    mut spawner: Spawner<IceballSpawnData>,
-    rapier_context: Res<RapierContext>,
+    mut query: Query<(&mut Cooldown, &GlobalTransform, &IceBallGun, &AbilityLevel, &GunId, &Targeting, &AbilityStats, Option<&ManaCost>)>,
+    mut spawner: Spawner<ProjectileSpawnData>,
+    target_finder: TargetFinder,
+    mut rng: ResMut<GlobalEntropy<WyRand>>,
+    guns: Res<Guns>,
+    mut mana: Query<&mut Mana, With<Player>>,
 ) {
-    for (ability, transform, gun, level) in query.iter_mut() {
+    for (ability, transform, gun, level, gun_id, targeting, stats, cost) in query.iter_mut() {
         if level.level == 0 {
             continue;
         }
         if ability.timer.just_finished() {
             let translation = transform.translation();
-            if let Some((entity, projection)) = rapier_context.project_point(
-                to_vec2(translation),
-                true,
-                QueryFilter {
-                    flags: Default::default(),
-                    groups: Some(CollisionGroups::new(game_layer::ENEMY, game_layer::ENEMY)), //is this filter correct?
-                    exclude_collider: None,
-                    exclude_rigid_body: None,
-                    predicate: None,
-                },
-            ) {
-                // The collider closest to the point has this `handle`.
-                // info!("Projected point on entity {:?}. Point projection: {}", entity, projection.point);
-                // info!("Point was inside of the collider shape: {}", projection.is_inside);
-
-                let mut delta = projection.point - to_vec2(translation);
-                delta = delta.normalize();
+            let Some(target) = target_finder.find(targeting, translation.xy(), &mut rng) else {
+                continue;
+            };
+            if !try_spend_mana(&mut mana, cost) {
+                continue;
+            }
 
-                let mut spawn_data = IceballSpawnData::get_data_for_level(level.level);
-                spawn_data.data.position = translation;
-                spawn_data.data.direction = delta;
-                spawner.spawn(Object::Iceball, spawn_data);
-                // spawn_fireball(&mut commands, &gun, translation, delta, &atlases);
+            let gun_data = guns.get(gun_id);
+            let mut spawn_data = ProjectileSpawnData::from_gun_data(gun_data, level.level);
+            spawn_data.data.position = translation;
+            spawn_data.stats = stats.stats.clone();
+            spawn_data.payloads.push(OnHitPayload::Cold(ApplyColdOnTouch {
+                multiplier: gun_data.stat("slow_amount", level.level),
+                seconds: gun_data.stat("slow_seconds", level.level),
+            }));
+            let aim = (target - translation.xy()).normalize_or_zero();
+            for direction in fan_out(aim, 1 + stats.stats.amount(), EXTRA_PROJECTILE_SPREAD) {
+                let mut spawn_data = spawn_data.clone();
+                spawn_data.data.direction = direction;
+                spawner.spawn(Object::Projectile, spawn_data);
             }
+            // spawn_fireball(&mut commands, &gun, translation, delta, &atlases);
         }
     }
 }
 
 pub fn fireball_gun(
-    aim_direction: Res<AimDirection>,
-    mut query: Query<(&mut Cooldown, &GlobalTransform, &FireBallGun, &AbilityLevel)>,
-    mut spawner: Spawner<FireballSpawnData>,
-    rapier_context: Res<RapierContext>,
+    mut query: Query<(&mut Cooldown, &GlobalTransform, &FireBallGun, &AbilityLevel, &GunId, &Targeting, &AbilityStats, Option<&ManaCost>)>,
+    mut spawner: Spawner<ProjectileSpawnData>,
+    target_finder: TargetFinder,
+    mut rng: ResMut<GlobalEntropy<WyRand>>,
+    guns: Res<Guns>,
+    mut mana: Query<&mut Mana, With<Player>>,
 ) {
-    for (ability, transform, gun, level) in query.iter_mut() {
+    for (ability, transform, gun, level, gun_id, targeting, stats, cost) in query.iter_mut() {
         if level.level == 0 {
             continue;
         }
         if ability.timer.just_finished() {
             let translation = transform.translation();
-            if let Some((entity, projection)) = rapier_context.project_point(
-                to_vec2(translation),
-                true,
-                QueryFilter {
-                    flags: Default::default(),
-                    groups: Some(CollisionGroups::new(game_layer::ENEMY, game_layer::ENEMY)), //is this filter correct?
-                    exclude_collider: None,
-                    exclude_rigid_body: None,
-                    predicate: None,
+            let Some(target) = target_finder.find(targeting, translation.xy(), &mut rng) else {
+                continue;
+            };
+            if !try_spend_mana(&mut mana, cost) {
+                continue;
+            }
+
+            let gun_data = guns.get(gun_id);
+            let mut spawn_data = ProjectileSpawnData::from_gun_data(gun_data, level.level);
+            spawn_data.data.position = translation;
+            spawn_data.data.direction = (target - translation.xy()).normalize_or_zero();
+            spawn_data.stats = stats.stats.clone();
+            spawn_data.payloads.push(OnHitPayload::Burn(ApplyBurnOnTouch {
+                damage_per_tick: gun_data.stat("burn_damage", level.level),
+                tick_seconds: gun_data.stat("burn_tick_seconds", level.level),
+                seconds: gun_data.stat("burn_seconds", level.level),
+                stacking: gun_data.burn_stacking,
+            }));
+            let explosion_radius = gun_data.stat_or("explosion_radius", level.level, 0.0);
+            if explosion_radius > 0.0 {
+                spawn_data.payloads.push(OnHitPayload::Explosion(ExplodeOnExpire {
+                    radius: explosion_radius,
+                    damage: gun_data.stat_or("explosion_damage", level.level, spawn_data.data.damage),
+                    damage_type: gun_data.damage_type,
+                    min_multiplier: gun_data.stat_or("explosion_min_multiplier", level.level, 0.5),
+                    knockback: gun_data.stat_or("explosion_knockback", level.level, 0.0),
+                    status: None,
+                }));
+            }
+            let aim = spawn_data.data.direction;
+            for direction in fan_out(aim, 1 + stats.stats.amount(), EXTRA_PROJECTILE_SPREAD) {
+                let mut spawn_data = spawn_data.clone();
+                spawn_data.data.direction = direction;
+                spawner.spawn(Object::Projectile, spawn_data);
+            }
+            // spawn_fireball(&mut commands, &gun, translation, delta, &atlases);
+        }
+    }
+}
+
+pub fn orbit_weapon(
+    mut query: Query<(&Cooldown, &OrbitGun, &AbilityLevel, &Parent, &GunId, &AbilityStats)>,
+    mut spawner: Spawner<OrbitSpawnData>,
+    guns: Res<Guns>,
+) {
+    for (ability, _, level, player, gun_id, stats) in query.iter_mut() {
+        if level.level == 0 || !ability.timer.just_finished() {
+            continue;
+        }
+        let data = OrbitData::from_gun_data(guns.get(gun_id), level.level);
+        let count = data.count as u32 + stats.stats.amount();
+        for index in 0..count {
+            spawner.spawn(
+                Object::Orbit,
+                OrbitSpawnData {
+                    parent: player.get(),
+                    //spread evenly around the circle
+                    angle: index as f32 / count as f32 * std::f32::consts::TAU,
+                    data,
+                    stats: stats.stats.clone(),
                 },
-            ) {
-                // The collider closest to the point has this `handle`.
-                // info!("Projected point on entity {:?}. Point projection: {}", entity, projection.point);
-                // info!("Point was inside of the collider shape: {}", projection.is_inside);
+            );
+        }
+    }
+}
 
-                let mut delta = projection.point - to_vec2(translation);
-                delta = delta.normalize();
+pub fn move_orbiting_projectiles(
+    mut query: Query<(Entity, &mut Orbiting, &mut Transform, &Parent)>,
+    parents: Query<&Transform, Without<Orbiting>>,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    for (entity, mut orbiting, mut transform, parent) in query.iter_mut() {
+        orbiting.uptime.tick(time.delta());
+        if orbiting.uptime.finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+        orbiting.angle += orbiting.angular_speed * time.delta_seconds();
+        //radius is in world units, so undo the parent's scale
+        let parent_scale = parents.get(parent.get()).map_or(Vec2::ONE, |parent| parent.scale.xy());
+        let offset = Vec2::from_angle(orbiting.angle) * orbiting.radius / parent_scale;
+        transform.translation = offset.extend(transform.translation.z);
+    }
+}
 
-                let mut spawn_data = FireballSpawnData::get_data_for_level(level.level);
-                spawn_data.position = translation;
-                spawn_data.direction = aim_direction.0;
-                spawner.spawn(Object::Fireball, spawn_data);
-                // spawn_fireball(&mut commands, &gun, translation, delta, &atlases);
+pub fn steer_homing_projectiles(
+    mut projectiles: Query<(&mut Homing, &mut Velocity, &GlobalTransform)>,
+    enemies: Query<(Entity, &GlobalTransform, &Health), With<Enemy>>,
+    time: Res<Time>,
+) {
+    for (mut homing, mut velocity, transform) in projectiles.iter_mut() {
+        let position = transform.translation().xy();
+        let locked_on = homing
+            .target
+            .and_then(|target| enemies.get(target).ok())
+            .filter(|(_, _, health)| health.value > 0.0)
+            .map(|(_, target_transform, _)| target_transform.translation().xy());
+        let target_position = match (homing.mode, locked_on) {
+            (HomingTarget::Original, Some(target_position)) => Some(target_position),
+            //nearest mode or the old target died, look for a new one
+            _ => {
+                let nearest = enemies
+                    .iter()
+                    .filter(|(_, _, health)| health.value > 0.0)
+                    .map(|(entity, enemy_transform, _)| (entity, enemy_transform.translation().xy()))
+                    .filter(|(_, enemy_position)| enemy_position.distance(position) <= homing.range)
+                    .min_by(|(_, a), (_, b)| {
+                        a.distance_squared(position).total_cmp(&b.distance_squared(position))
+                    });
+                homing.target = nearest.map(|(entity, _)| entity);
+                nearest.map(|(_, enemy_position)| enemy_position)
             }
+        };
+        let Some(target_position) = target_position else {
+            continue;
+        };
+
+        let speed = velocity.linvel.length();
+        let desired = (target_position - position).normalize_or_zero();
+        if speed <= DEFAULT_EPSILON || desired == Vec2::ZERO {
+            continue;
         }
+        let current = velocity.linvel / speed;
+        let max_turn = homing.turn_rate * time.delta_seconds();
+        let turn = current.angle_between(desired).clamp(-max_turn, max_turn);
+        velocity.linvel = Vec2::from_angle(turn).rotate(current) * speed;
     }
 }
 
+/// `count` directions spread evenly around `aim`, `spread` radians apart.
+pub fn fan_out(aim: Vec2, count: u32, spread: f32) -> Vec<Vec2> {
+    (0..count)
+        .map(|index| {
+            let offset = index as f32 - (count - 1) as f32 * 0.5;
+            Vec2::from_angle(offset * spread).rotate(aim)
+        })
+        .collect()
+}
+
 #[derive(Event)]
 pub struct ShootEvent(pub Entity);
 
+/// Reload was pressed again while the gun was already reloading.
+#[derive(Event)]
+pub struct ActiveReloadEvent(pub Entity);
+
 pub fn pistol_gun(
-    aim_direction: Res<AimDirection>,
-    mut query: Query<(&mut Cooldown, &GlobalTransform, &PistolGun, &AbilityLevel, &Ammo, &Children)>,
-    mut spawner: Spawner<PistolBulletSpawnData>,
+    mut query: Query<(&mut Cooldown, &GlobalTransform, &PistolGun, &AbilityLevel, &Ammo, &Children, &GunId, Option<&ActiveReloadBuff>, &Targeting, &AbilityStats)>,
+    mut spawner: Spawner<ProjectileSpawnData>,
+    target_finder: TargetFinder,
+    mut rng: ResMut<GlobalEntropy<WyRand>>,
+    chambered_query: Query<&SpecialRound, With<Chambered>>,
     mut commands: Commands,
-    rapier_context: Res<RapierContext>,
     mut shoot_event: EventReader<ShootEvent>,
+    guns: Res<Guns>,
 ) {
     for event in shoot_event.read() {
-        println!("Got shoot event.");
-        for (mut ability, transform, gun, level, mut ammo, children) in query.iter_mut() {
-            if level.level == 0 || children.len() == 0 {
+        let Ok((mut ability, transform, gun, level, ammo, children, gun_id, buff, targeting, stats)) = query.get_mut(event.0) else {
+            //not a pistol
+            continue;
+        };
+        if level.level == 0 || children.len() == 0 {
+            continue;
+        }
+        if ability.timer.finished() {
+            let translation = transform.translation();
+            let Some(target) = target_finder.find(targeting, translation.xy(), &mut rng) else {
                 continue;
+            };
+            ability.timer.reset();
+            let bullet = children.get(0).unwrap();
+            let mut spawn_data = ProjectileSpawnData::from_gun_data(guns.get(gun_id), level.level);
+            spawn_data.data.position = translation;
+            spawn_data.data.direction = (target - translation.xy()).normalize_or_zero();
+            spawn_data.stats = stats.stats.clone();
+            if let Some(buff) = buff {
+                spawn_data.data.damage *= buff.damage_multiplier;
+            }
+            if let Ok(round) = chambered_query.get(*bullet) {
+                load_special_round(round, &mut spawn_data);
+            }
+            spawner.spawn(Object::Projectile, spawn_data);
+            commands.entity(*bullet).despawn_recursive();
+        }
+    }
+}
+
+pub fn shotgun_gun(
+    mut query: Query<(&mut Cooldown, &GlobalTransform, &ShotgunGun, &AbilityLevel, &Children, &GunId, Option<&ActiveReloadBuff>, &Targeting, &AbilityStats)>,
+    mut spawner: Spawner<ProjectileSpawnData>,
+    target_finder: TargetFinder,
+    mut rng: ResMut<GlobalEntropy<WyRand>>,
+    chambered_query: Query<&SpecialRound, With<Chambered>>,
+    mut commands: Commands,
+    mut shoot_event: EventReader<ShootEvent>,
+    guns: Res<Guns>,
+) {
+    for event in shoot_event.read() {
+        let Ok((mut ability, transform, _, level, children, gun_id, buff, targeting, stats)) = query.get_mut(event.0) else {
+            //not a shotgun
+            continue;
+        };
+        if level.level == 0 || children.len() == 0 || !ability.timer.finished() {
+            continue;
+        }
+        let translation = transform.translation();
+        let Some(target) = target_finder.find(targeting, translation.xy(), &mut rng) else {
+            continue;
+        };
+        ability.timer.reset();
+        let gun_data = guns.get(gun_id);
+        let aim = (target - translation.xy()).normalize_or_zero();
+        //one shell fires every pellet
+        let shell = children.get(0).unwrap();
+        let pellets = (gun_data.stat("pellets", level.level) as u32).max(1) + stats.stats.amount();
+        let spread = gun_data.stat("spread_degrees", level.level).to_radians();
+
+        for pellet in 0..pellets {
+            //spread pellets evenly across the cone, a single pellet goes straight
+            let fraction = if pellets > 1 {
+                pellet as f32 / (pellets - 1) as f32
+            } else {
+                0.5
+            };
+            let angle = spread * (fraction - 0.5);
+
+            let mut spawn_data = ProjectileSpawnData::from_gun_data(gun_data, level.level);
+            spawn_data.data.position = translation;
+            spawn_data.data.direction = Vec2::from_angle(angle).rotate(aim);
+            spawn_data.stats = stats.stats.clone();
+            if let Some(buff) = buff {
+                spawn_data.data.damage *= buff.damage_multiplier;
+            }
+            //every pellet gets the shell's round
+            if let Ok(round) = chambered_query.get(*shell) {
+                load_special_round(round, &mut spawn_data);
+            }
+            spawn_data.payloads.push(OnHitPayload::DamageFalloff(DamageFalloff {
+                origin: translation.xy(),
+                base_damage: spawn_data.data.damage,
+                start_distance: gun_data.stat("falloff_start", level.level),
+                end_distance: gun_data.stat("falloff_end", level.level),
+                min_multiplier: gun_data.stat("falloff_min_multiplier", level.level),
+            }));
+            spawner.spawn(Object::Projectile, spawn_data);
+        }
+        commands.entity(*shell).despawn_recursive();
+    }
+}
+
+pub fn sniper_gun(
+    mut query: Query<(&mut Cooldown, &GlobalTransform, &SniperGun, &AbilityLevel, &Children, &GunId, Option<&ActiveReloadBuff>, &Targeting, &AbilityStats)>,
+    //the target finder reads health too, so it can't live next to the health query
+    mut targets: ParamSet<(
+        TargetFinder,
+        Query<(Entity, &mut Health, &Transform, Option<&Resistances>, Option<&PlayerStats>)>,
+    )>,
+    enemy_query: Query<(), With<Enemy>>,
+    mut damage_spawner: Spawner<DamageTextSpawnData>,
+    mut particle_spawner: Spawner<ParticleSpawnData>,
+    rapier_context: Res<RapierContext>,
+    mut commands: Commands,
+    mut shoot_event: EventReader<ShootEvent>,
+    guns: Res<Guns>,
+    crit_bonus: Query<&CritBonus, With<Player>>,
+    mut rng: ResMut<GlobalEntropy<WyRand>>,
+    mut special_ammo: Query<&mut SpecialAmmoQueue>,
+) {
+    for event in shoot_event.read() {
+        let Ok((mut ability, transform, _, level, children, gun_id, buff, targeting, stats)) = query.get_mut(event.0) else {
+            //not a sniper
+            continue;
+        };
+        if level.level == 0 || children.len() == 0 || !ability.timer.finished() {
+            continue;
+        }
+        let origin = transform.translation().xy();
+        let Some(target) = targets.p0().find(targeting, origin, &mut rng) else {
+            continue;
+        };
+        let direction = (target - origin).normalize_or_zero();
+        if direction == Vec2::ZERO {
+            continue;
+        }
+        ability.timer.reset();
+        let gun_data = guns.get(gun_id);
+        let damage_multiplier = stats.stats.damage_multiplier(gun_data.damage_type);
+        let damage = gun_data.stat("damage", level.level) * buff.map_or(1.0, |buff| buff.damage_multiplier) * damage_multiplier;
+        let pierce = gun_data.stat("pierce", level.level) as usize;
+        let crit = crit_with_stats(gun_data.crit_for_level(level.level), &crit_bonus, &stats.stats);
+
+        //walls stop the shot
+        let mut range = gun_data.stat("range", level.level);
+        if let Some((_, wall_distance)) = rapier_context.cast_ray(
+            origin,
+            direction,
+            range,
+            true,
+            QueryFilter::new().groups(CollisionGroups::new(game_layer::PLAYER, game_layer::GROUND)),
+        ) {
+            range = wall_distance;
+        }
+
+        //intersections are not sorted, so collect them and hit the closest ones first
+        let mut hits: Vec<(Entity, f32)> = vec![];
+        rapier_context.intersections_with_ray(
+            origin,
+            direction,
+            range,
+            true,
+            QueryFilter::new().groups(CollisionGroups::new(game_layer::PLAYER, game_layer::ENEMY)),
+            |entity, intersection| {
+                if enemy_query.contains(entity) {
+                    hits.push((entity, intersection.toi));
+                }
+                true
+            },
+        );
+        hits.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+        let mut tracer_length = range;
+        for (index, (enemy, distance)) in hits.iter().take(pierce + 1).enumerate() {
+            let mut damager = DamageOnTouch {
+                value: damage,
+                damage_type: gun_data.damage_type,
+                crit,
+                knockback: gun_data.stat_or("knockback", level.level, 0.0),
+                ..default()
+            };
+            try_deal_damage(&mut commands, &mut damager, origin, targets.p1().get_mut(*enemy), &mut damage_spawner, &mut rng);
+            if index == pierce {
+                //out of pierce, the shot stops in this enemy
+                tracer_length = *distance;
+            }
+        }
+        if hits.len() > pierce {
+            //used up every pierce, reward it with a piercing round for the revolver
+            if let Ok(mut queue) = special_ammo.get_single_mut() {
+                queue.push(SpecialRound::Piercing);
+            }
+        }
+
+        spawn_tracer(
+            &mut particle_spawner,
+            origin,
+            origin + direction * tracer_length,
+            &gun_data.sprite_sheet,
+            Color::rgb(1.0, 0.9, 0.5),
+        );
+        commands.entity(*children.get(0).unwrap()).despawn_recursive();
+    }
+}
+
+pub fn chain_lightning_gun(
+    query: Query<(&Cooldown, &GlobalTransform, &ChainLightningGun, &AbilityLevel, &GunId, &Targeting, &AbilityStats)>,
+    //the target finder reads health too, so it can't live next to the health query
+    mut targets: ParamSet<(
+        TargetFinder,
+        Query<(Entity, &mut Health, &Transform, Option<&Resistances>, Option<&PlayerStats>)>,
+    )>,
+    enemy_query: Query<&GlobalTransform, With<Enemy>>,
+    mut damage_spawner: Spawner<DamageTextSpawnData>,
+    mut particle_spawner: Spawner<ParticleSpawnData>,
+    rapier_context: Res<RapierContext>,
+    mut commands: Commands,
+    guns: Res<Guns>,
+    crit_bonus: Query<&CritBonus, With<Player>>,
+    mut rng: ResMut<GlobalEntropy<WyRand>>,
+) {
+    for (ability, transform, _, level, gun_id, targeting, stats) in query.iter() {
+        if level.level == 0 || !ability.timer.just_finished() {
+            continue;
+        }
+        let gun_data = guns.get(gun_id);
+        let stats = &stats.stats;
+        //extra amount is extra jumps
+        let jumps = gun_data.stat("jumps", level.level) as usize + stats.amount() as usize;
+        let jump_radius = gun_data.stat("jump_radius", level.level) * stats.get(Stat::Area);
+        let falloff = gun_data.stat("jump_falloff", level.level);
+        let crit = crit_with_stats(gun_data.crit_for_level(level.level), &crit_bonus, stats);
+
+        let mut from = transform.translation().xy();
+        let Some(target) = targets.p0().find(targeting, from, &mut rng) else {
+            continue;
+        };
+        //the targeting strategy picks a spot, the first strike hits the enemy closest to it.
+        //every jump after that goes to the nearest enemy within jump_radius
+        let mut strike = nearest_enemy_in_radius(&rapier_context, &enemy_query, target, jump_radius, &[]);
+
+        let mut hit: Vec<Entity> = vec![];
+        let mut damage = gun_data.stat("damage", level.level) * stats.damage_multiplier(gun_data.damage_type);
+        for _ in 0..=jumps {
+            let Some((enemy, position)) = strike else {
+                break;
+            };
+            let mut damager = DamageOnTouch {
+                value: damage,
+                damage_type: gun_data.damage_type,
+                crit,
+                ..default()
+            };
+            try_deal_damage(&mut commands, &mut damager, from, targets.p1().get_mut(enemy), &mut damage_spawner, &mut rng);
+            spawn_tracer(
+                &mut particle_spawner,
+                from,
+                position,
+                &gun_data.sprite_sheet,
+                Color::rgb(0.6, 0.8, 1.0),
+            );
+
+            hit.push(enemy);
+            from = position;
+            damage *= falloff;
+            strike = nearest_enemy_in_radius(&rapier_context, &enemy_query, from, jump_radius, &hit);
+        }
+    }
+}
+
+/// Closest enemy whose collider overlaps a circle around `position`, skipping `exclude`.
+fn nearest_enemy_in_radius(
+    rapier_context: &RapierContext,
+    enemy_query: &Query<&GlobalTransform, With<Enemy>>,
+    position: Vec2,
+    radius: f32,
+    exclude: &[Entity],
+) -> Option<(Entity, Vec2)> {
+    let mut nearest: Option<(Entity, Vec2)> = None;
+    rapier_context.intersections_with_shape(
+        position,
+        0.0,
+        &Collider::ball(radius),
+        QueryFilter::new().groups(CollisionGroups::new(game_layer::PLAYER, game_layer::ENEMY)),
+        |entity| {
+            if exclude.contains(&entity) {
+                return true;
             }
-            if ability.timer.finished() {
-                ability.timer.reset();
-                let translation = transform.translation();
-                let bullet = children.get(0).unwrap();
-                let mut spawn_data = PistolBulletSpawnData::get_data_for_level(level.level);
-                spawn_data.data.position = translation;
-                spawn_data.data.direction = aim_direction.0;
-                spawn_data.bullet = Some(*bullet);
-                spawner.spawn(Object::PistolBullet, spawn_data);
-                commands.entity(*bullet).remove_parent();
-                // spawn_fireball(&mut commands, &gun, translation, delta, &atlases);
+            let Ok(enemy_transform) = enemy_query.get(entity) else {
+                return true;
+            };
+            let enemy_position = enemy_transform.translation().xy();
+            if nearest.map_or(true, |(_, best)| {
+                enemy_position.distance_squared(position) < best.distance_squared(position)
+            }) {
+                nearest = Some((entity, enemy_position));
             }
+            true
+        },
+    );
+    nearest
+}
+
+//special round burns never stack
+fn special_round_burn(seconds: f32) -> ApplyBurnOnTouch {
+    ApplyBurnOnTouch {
+        damage_per_tick: SPECIAL_ROUND_BURN_DAMAGE_PER_TICK,
+        tick_seconds: SPECIAL_ROUND_BURN_TICK_SECONDS,
+        seconds,
+        stacking: StatusStacking::Refresh,
+    }
+}
+
+/// Turns the special round a bullet was chambered with into projectile stats and payloads.
+pub fn load_special_round(round: &SpecialRound, spawn_data: &mut ProjectileSpawnData) {
+    match round {
+        SpecialRound::Frozen => {
+            spawn_data.data.damage_type = DamageType::Ice;
+            spawn_data.payloads.push(OnHitPayload::Cold(ApplyColdOnTouch {
+                multiplier: FROZEN_ROUND_COLD_MULTIPLIER,
+                seconds: FROZEN_ROUND_COLD_SECONDS,
+            }));
+        }
+        SpecialRound::Incendiary => {
+            spawn_data.data.damage_type = DamageType::Fire;
+            spawn_data.payloads.push(OnHitPayload::Burn(special_round_burn(INCENDIARY_ROUND_BURN_SECONDS)));
+        }
+        SpecialRound::Explosive => {
+            spawn_data.payloads.push(OnHitPayload::Explosion(ExplodeOnExpire {
+                radius: EXPLOSIVE_ROUND_RADIUS,
+                damage: spawn_data.data.damage * EXPLOSIVE_ROUND_DAMAGE_MULTIPLIER,
+                damage_type: DamageType::Fire,
+                min_multiplier: EXPLOSIVE_ROUND_MIN_MULTIPLIER,
+                knockback: EXPLOSIVE_ROUND_KNOCKBACK,
+                status: Some(ExplosionStatus::Burn(special_round_burn(EXPLOSIVE_ROUND_BURN_SECONDS))),
+            }));
         }
+        SpecialRound::Piercing => {
+            spawn_data.data.pierce = spawn_data.data.pierce.saturating_add(PIERCING_ROUND_EXTRA_PIERCE);
+        }
+    }
+}
+
+/// Spawns a dotted line of particles from `start` to `end`.
+pub fn spawn_tracer(
+    spawner: &mut Spawner<ParticleSpawnData>,
+    start: Vec2,
+    end: Vec2,
+    sprite_sheet: &str,
+    color: Color,
+) {
+    let spacing = 12.0;
+    let steps = (start.distance(end) / spacing).ceil().max(1.0) as u32;
+    for step in 0..=steps {
+        spawner.spawn(
+            Object::Particle,
+            ParticleSpawnData {
+                position: start.lerp(end, step as f32 / steps as f32),
+                scale: Vec2::splat(0.3),
+                sprite_sheet: sprite_sheet.to_string(),
+                color,
+                animation: "Idle".to_string(),
+                parent: None,
+                lifetime: Lifetime::from_seconds(0.15),
+            },
+        );
+    }
+}
+
+pub fn apply_damage_falloff(mut query: Query<(&DamageFalloff, &Transform, &mut DamageOnTouch)>) {
+    for (falloff, transform, mut damage) in query.iter_mut() {
+        let distance = transform.translation.xy().distance(falloff.origin);
+        let range = (falloff.end_distance - falloff.start_distance).max(DEFAULT_EPSILON);
+        let t = ((distance - falloff.start_distance) / range).clamp(0.0, 1.0);
+        damage.value = falloff.base_damage * (1.0 - t * (1.0 - falloff.min_multiplier));
     }
 }
 
 
 // #[bevycheck::system]
 pub fn reload_gun_system(
-    mut query: Query<(Entity, &mut Ammo, &mut Reloading, Option<&Children>)>,
+    mut query: Query<(Entity, &mut Ammo, &mut Reloading, Option<&Children>, Has<LoadsSpecialRounds>)>,
+    mut special_ammo: Query<&mut SpecialAmmoQueue>,
     time: Res<Time>,
     mut commands: Commands,
 ) {
-    for (entity, mut ammo, mut reload, children) in query.iter_mut() {
+    for (entity, mut ammo, mut reload, children, loads_special_rounds) in query.iter_mut() {
         reload.timer.tick(time.delta());
+        reload.total.tick(time.delta());
 
-        if (children.is_some() && children.unwrap().len() as u16 == ammo.max_amount) {
+        //summon munition can leave a gun with more than max_amount
+        if (children.is_some() && children.unwrap().len() as u16 >= ammo.max_amount) {
             commands.entity(entity).remove::<Reloading>();
             continue;
         }
         if (reload.timer.just_finished()) {
             //add bullet
             //get stats from player?
-            //figure out queued statuses for reloaded bullets
-            commands.spawn((Chambered {}, ApplyColdOnTouch { multiplier: 1.0, seconds: 2.0 })).set_parent(entity);
+            chamber_bullet(&mut commands, entity, loads_special_rounds, &mut special_ammo);
         }
     }
 }
 
+/// Spawns one bullet into `gun`, taking the next special round from the queue if the gun uses them.
+pub fn chamber_bullet(
+    commands: &mut Commands,
+    gun: Entity,
+    loads_special_rounds: bool,
+    special_ammo: &mut Query<&mut SpecialAmmoQueue>,
+) {
+    let mut bullet = commands.spawn(Chambered {});
+    bullet.set_parent(gun);
+    if !loads_special_rounds {
+        return;
+    }
+    if let Some(round) = special_ammo.get_single_mut().ok().and_then(|mut queue| queue.pop()) {
+        bullet.insert(round);
+    }
+}
+
+/// Fills every gun with ammo, no matter how many there are, and skips any reload in progress.
+pub fn summon_munition(
+    query: Query<(&Cooldown, &SummonMunition, &AbilityLevel, &GunId, Option<&ManaCost>)>,
+    guns: Query<(Entity, &Ammo, Option<&Children>, Has<LoadsSpecialRounds>)>,
+    mut special_ammo: Query<&mut SpecialAmmoQueue>,
+    mut mana: Query<&mut Mana, With<Player>>,
+    gun_data: Res<Guns>,
+    mut rng: ResMut<GlobalEntropy<WyRand>>,
+    mut commands: Commands,
+) {
+    for (ability, _, level, gun_id, cost) in query.iter() {
+        if level.level == 0 || !ability.timer.just_finished() {
+            continue;
+        }
+        if guns.is_empty() || !try_spend_mana(&mut mana, cost) {
+            continue;
+        }
+        let gun_data = gun_data.get(gun_id);
+        let data = SummonMunitionData::from_gun_data(gun_data, level.level);
+        let rounds = &gun_data.special_rounds;
+        for (gun, ammo, children, loads_special_rounds) in guns.iter() {
+            let loaded = children.map_or(0, |bullets| bullets.len()) as u16;
+            for _ in loaded..ammo.max_amount {
+                chamber_bullet(&mut commands, gun, loads_special_rounds, &mut special_ammo);
+            }
+            //bonus rounds go on top, and don't stack up past the overfill
+            if rounds.is_empty() {
+                continue;
+            }
+            for _ in loaded.max(ammo.max_amount)..ammo.max_amount + data.overfill {
+                let round = rounds[rng.next_u32() as usize % rounds.len()];
+                commands.spawn((Chambered {}, round)).set_parent(gun);
+            }
+            commands.entity(gun).remove::<Reloading>();
+        }
+    }
+}
+
+pub fn active_reload_system(
+    mut events: EventReader<ActiveReloadEvent>,
+    query: Query<(&Ammo, &Reloading, Option<&Children>, Has<LoadsSpecialRounds>, &GunId)>,
+    mut special_ammo: Query<&mut SpecialAmmoQueue>,
+    guns: Res<Guns>,
+    mut commands: Commands,
+) {
+    for event in events.read() {
+        let Ok((ammo, reloading, children, loads_special_rounds, gun_id)) = query.get(event.0) else {
+            continue;
+        };
+        let Some(config) = guns.get(gun_id).active_reload.as_ref() else {
+            //this gun doesn't do active reloads
+            continue;
+        };
+        commands.entity(event.0).remove::<Reloading>();
+        if !config.in_window(reloading.total.fraction()) {
+            info!("Gun jammed!");
+            commands.entity(event.0).insert(Jammed {
+                timer: Timer::from_seconds(config.jam_seconds, Once),
+            });
+            continue;
+        }
+        let loaded = children.map_or(0, |bullets| bullets.len()) as u16;
+        for _ in loaded..ammo.max_amount {
+            chamber_bullet(&mut commands, event.0, loads_special_rounds, &mut special_ammo);
+        }
+        commands.entity(event.0).insert(ActiveReloadBuff {
+            damage_multiplier: config.damage_multiplier,
+            timer: Timer::from_seconds(config.buff_seconds, Once),
+        });
+    }
+}
+
 pub fn log_collisions(mut query: Query<(&CollidingEntities)>) {
     info!("{} colliding entities.", query.iter().len());
 }
@@ -232,16 +827,15 @@ pub fn log_collisions(mut query: Query<(&CollidingEntities)>) {
 pub fn apply_cold_on_collide(
     mut enemy_query: Query<(Entity), (With<Enemy>, With<MoveSpeed>)>,
     mut damage_query: Query<(&ApplyColdOnTouch, &CollidingEntities, &Collider)>,
-    mut commands: Commands,
+    mut status_writer: EventWriter<ApplyStatusEvent<Cold>>,
 ) {
     for (apply_cold, colliding_entities, _) in damage_query.iter() {
         for hit_enemy in colliding_entities.iter() {
-            //need unique tags for icy, etc. So a given effect only applies once...
             //entity 2 damages entity 1 if it can
             let slowed_entity = enemy_query.get_mut(hit_enemy);
             let slowing_entity = apply_cold;
 
-            try_slow(slowing_entity, slowed_entity, &mut commands);
+            try_slow(slowing_entity, slowed_entity, &mut status_writer);
         }
     }
 }
@@ -250,10 +844,9 @@ pub fn apply_cold_on_collide_start(
     mut collision_events: EventReader<CollisionEvent>,
     mut enemy_query: Query<(Entity), (With<Enemy>, With<MoveSpeed>)>,
     mut damage_query: Query<(&ApplyColdOnTouch), Without<CollidingEntities>>,
-    mut commands: Commands,
+    mut status_writer: EventWriter<ApplyStatusEvent<Cold>>,
 ) {
     for collision_event in collision_events.read() {
-        //need unique tags for icy, etc. So a given effect only applies once...
         match collision_event {
             CollisionEvent::Started(entity1, entity2, _flags) => {
                 {
@@ -263,7 +856,7 @@ pub fn apply_cold_on_collide_start(
 
                     match slowing_entity {
                         Ok(mut slower) => {
-                            try_slow(&*slower, slowed_entity, &mut commands);
+                            try_slow(&*slower, slowed_entity, &mut status_writer);
                         }
                         Err(_) => {}
                     }
@@ -274,7 +867,7 @@ pub fn apply_cold_on_collide_start(
                     let slowing_entity = damage_query.get_mut(*entity1);
                     match slowing_entity {
                         Ok(mut slower) => {
-                            try_slow(&*slower, slowed_entity, &mut commands);
+                            try_slow(&*slower, slowed_entity, &mut status_writer);
                         }
                         Err(_) => {}
                     }
@@ -285,52 +878,140 @@ pub fn apply_cold_on_collide_start(
     }
 }
 
+pub fn apply_burn_on_collide(
+    enemy_query: Query<Entity, With<Enemy>>,
+    burn_query: Query<(&ApplyBurnOnTouch, &CollidingEntities)>,
+    mut status_writer: EventWriter<ApplyStatusEvent<Burning>>,
+) {
+    for (apply_burn, colliding_entities) in burn_query.iter() {
+        for hit_enemy in colliding_entities.iter() {
+            try_burn(apply_burn, enemy_query.get(hit_enemy), &mut status_writer);
+        }
+    }
+}
 
-#[derive(Component, TemporaryComponent)]
-pub struct Damaged {
-    pub timer: Timer,
+pub fn apply_burn_on_collide_start(
+    mut collision_events: EventReader<CollisionEvent>,
+    enemy_query: Query<Entity, With<Enemy>>,
+    burn_query: Query<&ApplyBurnOnTouch, Without<CollidingEntities>>,
+    mut status_writer: EventWriter<ApplyStatusEvent<Burning>>,
+) {
+    for collision_event in collision_events.read() {
+        match collision_event {
+            CollisionEvent::Started(entity1, entity2, _flags) => {
+                //entity 2 burns entity 1 if it can
+                if let Ok(burner) = burn_query.get(*entity2) {
+                    try_burn(burner, enemy_query.get(*entity1), &mut status_writer);
+                }
+                //entity 1 burns entity 2 if it can
+                if let Ok(burner) = burn_query.get(*entity1) {
+                    try_burn(burner, enemy_query.get(*entity2), &mut status_writer);
+                }
+            }
+            _ => {}
+        }
+    }
 }
 
-///Be sure to register all T types in main!
-pub fn process_temporary_component<T>(
-    mut damaged: Query<(Entity, &mut T)>,
-    time: Res<Time>,
+fn try_burn(
+    burner: &ApplyBurnOnTouch,
+    target: Result<Entity, QueryEntityError>,
+    status_writer: &mut EventWriter<ApplyStatusEvent<Burning>>,
+) {
+    let Ok(entity) = target else {
+        return;
+    };
+    status_writer.send(
+        ApplyStatusEvent::new(
+            entity,
+            Burning {
+                damage_per_tick: burner.damage_per_tick,
+                tick_timer: Timer::from_seconds(burner.tick_seconds, TimerMode::Repeating),
+                timer: Timer::from_seconds(burner.seconds, Once),
+            },
+        )
+        .with_stacking(burner.stacking),
+    );
+}
+
+pub fn burn_damage_tick(
+    mut burning_query: Query<(Entity, &mut Burning)>,
+    mut health_query: Query<(Entity, &mut Health, &Transform, Option<&Resistances>, Option<&PlayerStats>)>,
+    mut spawner: Spawner<DamageTextSpawnData>,
     mut commands: Commands,
-) where
-    T: TemporaryComponent + Component,
-{
-    for (entity, mut damaged) in damaged.iter_mut() {
-        damaged.advance_timer(time.delta());
-        if (damaged.is_finished()) {
-            commands.entity(entity).remove::<T>();
+    time: Res<Time>,
+    mut rng: ResMut<GlobalEntropy<WyRand>>,
+) {
+    for (entity, mut burning) in burning_query.iter_mut() {
+        burning.tick_timer.tick(time.delta());
+        for _ in 0..burning.tick_timer.times_finished_this_tick() {
+            //burn ticks run on their own timer, so they don't need a hit cooldown
+            let mut damager = DamageOnTouch {
+                value: burning.damage_per_tick,
+                damage_type: DamageType::Fire,
+                ..default()
+            };
+            //burning doesn't knock back, so there's no source to speak of
+            try_deal_damage(&mut commands, &mut damager, Vec2::ZERO, health_query.get_mut(entity), &mut spawner, &mut rng);
         }
     }
 }
 
+/// Just got hit, only used to flash the sprite.
+#[derive(Component, TemporaryComponent)]
+pub struct Damaged {
+    pub timer: Timer,
+}
+
 pub fn deal_damage_on_collide(
-    mut health_query: Query<(Entity, &mut Health, &Transform), Without<Damaged>>,
+    mut health_query: Query<(Entity, &mut Health, &Transform, Option<&Resistances>, Option<&PlayerStats>)>,
     enemy_query: Query<(Entity, &Enemy)>, //HACK do something smarter.
-    mut damage_query: Query<(Entity, &mut DamageOnTouch, &CollidingEntities)>,//for continuous collision
+    mut damage_query: Query<(Entity, &mut DamageOnTouch, &GlobalTransform, &CollidingEntities, Option<&mut HitCooldown>)>,//for continuous collision
+    mut invulnerable: Query<&mut Invulnerable>,
     mut spawner: Spawner<DamageTextSpawnData>,
     mut commands: Commands,
+    mut rng: ResMut<GlobalEntropy<WyRand>>,
 ) {
-    for (entity, mut damager, collisions) in damage_query.iter_mut() {
+    for (entity, mut damager, source, collisions, mut hit_cooldown) in damage_query.iter_mut() {
         for collision in collisions.iter() {
             //entity 2 damages entity 1 if it can
-            let entity1_health = health_query.get_mut(collision);
-
-            try_deal_damage(&mut commands, &mut damager, entity1_health, &mut spawner);
+            try_hit(
+                &mut commands,
+                &mut damager,
+                source.translation().xy(),
+                hit_cooldown.as_deref_mut(),
+                &mut invulnerable,
+                collision,
+                health_query.get_mut(collision),
+                &mut spawner,
+                &mut rng,
+            );
         }
     }
 }
 
+pub fn tick_hit_cooldowns(
+    mut query: Query<&mut HitCooldown>,
+    mut invulnerable: Query<&mut Invulnerable>,
+    time: Res<Time>,
+) {
+    for mut cooldown in query.iter_mut() {
+        cooldown.tick(time.delta());
+    }
+    for mut invulnerable in invulnerable.iter_mut() {
+        invulnerable.timer.tick(time.delta());
+    }
+}
+
 pub fn deal_damage_on_collide_start(
     mut collision_events: EventReader<CollisionEvent>,
-    mut health_query: Query<(Entity, &mut Health, &Transform), Without<Damaged>>,
+    mut health_query: Query<(Entity, &mut Health, &Transform, Option<&Resistances>, Option<&PlayerStats>)>,
     enemy_query: Query<(Entity, &Enemy)>, //HACK do something smarter.
-    mut damage_query: Query<(Entity, &mut DamageOnTouch), Without<CollidingEntities>>,//one-time collisions only
+    mut damage_query: Query<(Entity, &mut DamageOnTouch, &GlobalTransform, Option<&mut HitCooldown>), Without<CollidingEntities>>,//one-time collisions only
+    mut invulnerable: Query<&mut Invulnerable>,
     mut spawner: Spawner<DamageTextSpawnData>,
     mut commands: Commands,
+    mut rng: ResMut<GlobalEntropy<WyRand>>,
 ) {
     for collision_event in collision_events.read() {
         match collision_event {
@@ -346,8 +1027,8 @@ pub fn deal_damage_on_collide_start(
                         continue;
                     }
                     match entity2_damage {
-                        Ok((_, mut damage)) => {
-                            try_deal_damage(&mut commands, &mut *damage, entity1_health, &mut spawner);
+                        Ok((_, mut damage, source, mut hit_cooldown)) => {
+                            try_hit(&mut commands, &mut *damage, source.translation().xy(), hit_cooldown.as_deref_mut(), &mut invulnerable, *entity1, entity1_health, &mut spawner, &mut rng);
                         }
                         Err(_) => {}//the colliding entity was not a damager.
                     }
@@ -357,8 +1038,8 @@ pub fn deal_damage_on_collide_start(
                     let entity2_health = health_query.get_mut(*entity2);
                     let entity1_damage = damage_query.get_mut(*entity1);
                     match entity1_damage {
-                        Ok((_, mut damage)) => {
-                            try_deal_damage(&mut commands, &mut *damage, entity2_health, &mut spawner);
+                        Ok((_, mut damage, source, mut hit_cooldown)) => {
+                            try_hit(&mut commands, &mut *damage, source.translation().xy(), hit_cooldown.as_deref_mut(), &mut invulnerable, *entity2, entity2_health, &mut spawner, &mut rng);
                         }
                         Err(_) => {}//the colliding entity was not a damager.
                     }
@@ -369,29 +1050,99 @@ pub fn deal_damage_on_collide_start(
     }
 }
 
-pub const DEFAULT_I_FRAMES: f32 = 0.1f32;
+//only how long the hit flash lasts, hit rate is up to each damager's HitCooldown and the player's Invulnerable
+pub const DAMAGED_FLASH_SECONDS: f32 = 0.1f32;
 
+/// `chance` is 0..1. Rolls go through the seeded entropy so a run can be replayed.
+pub fn roll_chance(rng: &mut impl RngCore, chance: f32) -> bool {
+    if chance <= 0.0 {
+        return false;
+    }
+    //the top rolls round up to exactly 1.0, so a sure thing has to skip the roll
+    if chance >= 1.0 {
+        return true;
+    }
+    (rng.next_u32() as f32 / u32::MAX as f32) < chance
+}
+
+/// Like `try_deal_damage`, but each damager only hits the same target once per its `HitCooldown`.
+/// Other damagers can still hit that target in the meantime, unless the target is `Invulnerable`.
+fn try_hit(
+    commands: &mut Commands,
+    damager: &mut DamageOnTouch,
+    source: Vec2,
+    hit_cooldown: Option<&mut HitCooldown>,
+    invulnerable: &mut Query<&mut Invulnerable>,
+    target: Entity,
+    target_health: Result<(Entity, Mut<Health>, &Transform, Option<&Resistances>, Option<&PlayerStats>), QueryEntityError>,
+    spawner: &mut Spawner<DamageTextSpawnData>,
+    rng: &mut GlobalEntropy<WyRand>,
+) {
+    if hit_cooldown.as_ref().is_some_and(|cooldown| !cooldown.is_ready(target)) {
+        return;
+    }
+    if invulnerable.get(target).is_ok_and(|invulnerable| invulnerable.is_active()) {
+        return;
+    }
+    let hit = target_health.is_ok();
+    try_deal_damage(commands, damager, source, target_health, spawner, rng);
+    if !hit {
+        return;
+    }
+    if let Some(cooldown) = hit_cooldown {
+        cooldown.start(target);
+    }
+    //reset right away instead of through commands, so the rest of this frame's hits see it
+    if let Ok(mut invulnerable) = invulnerable.get_mut(target) {
+        invulnerable.timer.reset();
+    }
+}
+
+/// `source` is where the hit came from, targets get knocked back away from it.
 fn try_deal_damage(
     commands: &mut Commands,
     entity1_damage: &mut DamageOnTouch,
-    entity2_health: Result<(Entity, Mut<Health>, &Transform), QueryEntityError>,
+    source: Vec2,
+    entity2_health: Result<(Entity, Mut<Health>, &Transform, Option<&Resistances>, Option<&PlayerStats>), QueryEntityError>,
     spawner: &mut Spawner<DamageTextSpawnData>,
+    rng: &mut GlobalEntropy<WyRand>,
 ) {
     match (entity2_health) {
-        (Ok((health_entity, mut health, transform))) => {
+        (Ok((health_entity, mut health, transform, resistances, stats))) => {
             entity1_damage.count_triggers += 1;
+            if stats.is_some_and(|stats| roll_chance(rng, stats.get(Stat::Dodge))) {
+                //dodged hits don't knock back either
+                return;
+            }
+            if entity1_damage.knockback > 0.0 {
+                let direction = (transform.translation.xy() - source).normalize_or_zero();
+                commands.entity(health_entity).insert(KnockedBack {
+                    velocity: direction * entity1_damage.knockback,
+                    timer: Timer::from_seconds(KNOCKBACK_SECONDS, Once),
+                });
+            }
             if entity1_damage.value <= 0.0 {
                 return;
             }
-            health.value -= entity1_damage.value;
+            let mut multiplier = resistances.map_or(1.0, |resistances| {
+                resistances.damage_multiplier(entity1_damage.damage_type)
+            });
+            let crit = roll_chance(rng, entity1_damage.crit.chance);
+            if crit {
+                multiplier *= entity1_damage.crit.multiplier;
+            }
+            let damage = (entity1_damage.value * multiplier).max(0.0);
+            health.value -= damage;
             commands.entity(health_entity).insert(Damaged {
-                timer: Timer::from_seconds(DEFAULT_I_FRAMES, Once),
+                timer: Timer::from_seconds(DAMAGED_FLASH_SECONDS, Once),
             });
             spawner.spawn(
                 Object::DamageNumber,
                 DamageTextSpawnData {
                     position: transform.translation.xy(),
-                    amount: entity1_damage.value as u32,
+                    amount: damage as u32,
+                    damage_type: entity1_damage.damage_type,
+                    crit,
                 },
             )
         }
@@ -402,19 +1153,17 @@ fn try_deal_damage(
 fn try_slow(
     entity1_damage: &ApplyColdOnTouch,
     entity2_health: Result<Entity, QueryEntityError>,
-    mut commands: &mut Commands,
+    status_writer: &mut EventWriter<ApplyStatusEvent<Cold>>,
 ) {
     match entity2_health {
         (Ok((entity))) => {
-            info!("will slow.");
-
-            // let mut child = commands.spawn((ParentMoveSpeedMultiplier { value: -slow.multiplier }, Lifetime::from_seconds(slow.seconds)));
-            // child.insert(Name ::new("slow effect"));
-            // child.set_parent(entity);
-            commands.entity(entity).insert(Cold {
-                multiplier: entity1_damage.multiplier,
-                timer: Timer::from_seconds(entity1_damage.seconds, TimerMode::Once),
-            });
+            status_writer.send(ApplyStatusEvent::new(
+                entity,
+                Cold {
+                    multiplier: entity1_damage.multiplier,
+                    timer: Timer::from_seconds(entity1_damage.seconds, TimerMode::Once),
+                },
+            ));
         }
         _ => {}
     }
@@ -431,6 +1180,74 @@ pub fn expire_bullets_on_hit(
     }
 }
 
+pub fn ricochet_bullets(
+    mut collision_events: EventReader<CollisionEvent>,
+    mut bullets: Query<(&mut Bullet, &mut Velocity, &GlobalTransform, Option<&Ricochet>), Without<Expired>>,
+    walls: Query<(), With<WallCollider>>,
+    enemies: Query<(Entity, &GlobalTransform, &Health), With<Enemy>>,
+    rapier_context: Res<RapierContext>,
+    time: Res<Time>,
+    mut commands: Commands,
+) {
+    for collision_event in collision_events.read() {
+        let CollisionEvent::Started(entity1, entity2, _flags) = collision_event else {
+            continue;
+        };
+        for (bullet_entity, other) in [(*entity1, *entity2), (*entity2, *entity1)] {
+            let Ok((mut bullet, mut velocity, transform, ricochet)) = bullets.get_mut(bullet_entity) else {
+                continue;
+            };
+            let position = transform.translation().xy();
+
+            if walls.contains(other) {
+                //bullets are sensors, so walls only stop them if we do it ourselves
+                if ricochet.is_none() || bullet.bounces == 0 {
+                    commands.entity(bullet_entity).insert(Expired {});
+                    continue;
+                }
+                bullet.bounces -= 1;
+                let normal = wall_normal(&rapier_context, position, velocity.linvel, time.delta_seconds());
+                velocity.linvel -= 2.0 * velocity.linvel.dot(normal) * normal;
+                continue;
+            }
+
+            let Some(ricochet) = ricochet else {
+                continue;
+            };
+            if bullet.bounces == 0 || !enemies.contains(other) {
+                continue;
+            }
+            let next_target = enemies
+                .iter()
+                .filter(|(entity, _, health)| *entity != other && health.value > 0.0)
+                .map(|(_, enemy_transform, _)| enemy_transform.translation().xy())
+                .filter(|enemy_position| enemy_position.distance(position) <= ricochet.range)
+                .min_by(|a, b| a.distance_squared(position).total_cmp(&b.distance_squared(position)));
+            let Some(next_target) = next_target else {
+                continue;
+            };
+            bullet.bounces -= 1;
+            //the ricochet pays for this hit instead of pierce
+            bullet.pierce = bullet.pierce.saturating_add(1);
+            velocity.linvel = (next_target - position).normalize_or_zero() * velocity.linvel.length();
+        }
+    }
+}
+
+fn wall_normal(rapier_context: &RapierContext, position: Vec2, velocity: Vec2, delta_seconds: f32) -> Vec2 {
+    let direction = velocity.normalize_or_zero();
+    //step back to about where the bullet was before it hit and look for the wall ahead of it
+    let origin = position - velocity * delta_seconds;
+    let max_distance = velocity.length() * delta_seconds * 2.0 + PIXEL_SCALE;
+    let filter = QueryFilter::new().groups(CollisionGroups::new(game_layer::PLAYER, game_layer::GROUND));
+    rapier_context
+        .cast_ray_and_get_normal(origin, direction, max_distance, true, filter)
+        .map(|(_, hit)| hit.normal)
+        .filter(|normal| *normal != Vec2::ZERO)
+        //started inside the wall, just send it back the way it came
+        .unwrap_or(-direction)
+}
+
 pub fn expired_bullets_explode(
     mut bullets: Query<(Entity, &Bullet, &Transform, &Name), With<Expired>>,
     mut commands: Commands,
@@ -455,6 +1272,61 @@ pub fn expired_bullets_explode(
     }
 }
 
+pub fn explode_expired_projectiles(
+    explosions: Query<(&ExplodeOnExpire, &GlobalTransform), With<Expired>>,
+    mut health_query: Query<(Entity, &mut Health, &Transform, Option<&Resistances>, Option<&PlayerStats>)>,
+    rapier_context: Res<RapierContext>,
+    mut damage_spawner: Spawner<DamageTextSpawnData>,
+    mut burn_writer: EventWriter<ApplyStatusEvent<Burning>>,
+    mut cold_writer: EventWriter<ApplyStatusEvent<Cold>>,
+    mut rng: ResMut<GlobalEntropy<WyRand>>,
+    mut commands: Commands,
+) {
+    for (explosion, transform) in explosions.iter() {
+        let center = transform.translation().xy();
+        let mut caught: Vec<Entity> = vec![];
+        rapier_context.intersections_with_shape(
+            center,
+            0.0,
+            &Collider::ball(explosion.radius),
+            QueryFilter::new().groups(CollisionGroups::new(game_layer::PLAYER, game_layer::ENEMY)),
+            |entity| {
+                caught.push(entity);
+                true
+            },
+        );
+
+        for enemy in caught {
+            let target = health_query.get_mut(enemy);
+            let Ok((_, _, enemy_transform, _)) = &target else {
+                continue;
+            };
+            let t = (enemy_transform.translation.xy().distance(center) / explosion.radius.max(DEFAULT_EPSILON)).clamp(0.0, 1.0);
+            let mut damager = DamageOnTouch {
+                value: explosion.damage * (1.0 - t * (1.0 - explosion.min_multiplier)),
+                damage_type: explosion.damage_type,
+                knockback: explosion.knockback,
+                ..default()
+            };
+            try_deal_damage(&mut commands, &mut damager, center, target, &mut damage_spawner, &mut rng);
+            match &explosion.status {
+                Some(ExplosionStatus::Burn(burn)) => try_burn(burn, Ok(enemy), &mut burn_writer),
+                Some(ExplosionStatus::Cold(cold)) => try_slow(cold, Ok(enemy), &mut cold_writer),
+                None => {}
+            }
+        }
+
+        commands.spawn((
+            DebugCircle {
+                radius: explosion.radius,
+                color: Color::ORANGE_RED,
+            },
+            SpatialBundle::from_transform(Transform::from_translation(center.extend(0.0))),
+            Lifetime::from_seconds(0.3),
+        ));
+    }
+}
+
 pub fn expire_entities(
     mut lifetimes: Query<(Entity, &mut Lifetime)>,
     mut commands: Commands,
@@ -562,27 +1434,97 @@ pub fn destroy_after_death_anim(
 pub struct DamageTextSpawnData {
     position: Vec2,
     amount: u32,
-    //type
+    damage_type: DamageType,
+    crit: bool,
+}
+
+pub struct OrbitSpawnData {
+    pub parent: Entity,
+    pub angle: f32,
+    pub data: OrbitData,
+    pub stats: PlayerStats,
+}
+
+pub fn spawn_orbit_projectile(
+    In(data): In<OrbitSpawnData>,
+    mut commands: Commands,
+    atlases: Res<Atlases>,
+    crit_bonus: Query<&CritBonus, With<Player>>,
+) {
+    if commands.get_entity(data.parent).is_none() {
+        return;
+    }
+    let stats = &data.stats;
+    let crit = crit_with_stats(Crit::default(), &crit_bonus, stats);
+    let bundle = OrbitProjectileBundle {
+        sprite_sheet: AnimatedSpriteBundle {
+            animator: SpriteAnimator::from_anim(AnimHandle::from_index(0)),
+            spritesheet: atlases
+                .sprite_sheets
+                .get("fireball")
+                .expect("failed to find asset for orbit projectile!")
+                .clone(),
+            sprite_bundle: SpriteSheetBundle {
+                //placed on the circle by move_orbiting_projectiles
+                transform: Transform::from_translation(Vec3::new(0.0, 0.0, 0.1))
+                    .with_scale(Vec2::splat(0.5 * stats.get(Stat::Area)).extend(1.0)),
+                ..default()
+            },
+            ..Default::default()
+        },
+        collider: Collider::ball(8.0),
+        collision_layers: CollisionGroups::new(game_layer::PLAYER, game_layer::ENEMY),
+        active_events: ActiveEvents::COLLISION_EVENTS,
+        name: Name::new("orbit"),
+        sensor: Default::default(),
+        damage: DamageOnTouch {
+            value: data.data.damage * stats.damage_multiplier(DamageType::default()),
+            crit,
+            ..default()
+        },
+        collisions: CollidingEntities::default(),
+        hit_cooldown: HitCooldown::from_seconds(data.data.hit_cooldown_seconds),
+        orbiting: Orbiting {
+            angle: data.angle,
+            radius: data.data.radius,
+            angular_speed: data.data.angular_speed * stats.get(Stat::ProjectileSpeed),
+            uptime: Timer::from_seconds(data.data.uptime_seconds * stats.get(Stat::Duration), Once),
+        },
+    };
+    commands.spawn(bundle).set_parent(data.parent);
 }
 
 pub struct FlaskSpawnData {
     gun: Flask,
     position: Vec2,
     pub scale: f32,
-    pub cooldown: f32,
     pub damage: f32,
+    pub damage_type: DamageType,
+    pub crit: Crit,
     duration_seconds: f32,
+    pub hit_cooldown_seconds: f32,
+    pub burn: ApplyBurnOnTouch,
+    pub stats: PlayerStats,
 }
 
-impl LevelableData for FlaskSpawnData {
-    fn get_data_for_level(level: u8) -> Self {
+impl GunLevelableData for FlaskSpawnData {
+    fn from_gun_data(gun: &GunData, level: u8) -> Self {
         Self {
             gun: Flask {},
             position: Default::default(),
-            scale: 5.0 + (level as f32),
-            cooldown: clamp(10.0 - (1.25 * level as f32), 0.5, 100.0),
-            damage: 1.0,
-            duration_seconds: 2.0,
+            scale: gun.stat("scale", level),
+            damage: gun.stat("damage", level),
+            damage_type: gun.damage_type,
+            crit: gun.crit_for_level(level),
+            duration_seconds: gun.stat("duration", level),
+            hit_cooldown_seconds: gun.stat("hit_cooldown", level),
+            burn: ApplyBurnOnTouch {
+                damage_per_tick: gun.stat("burn_damage", level),
+                tick_seconds: gun.stat("burn_tick_seconds", level),
+                seconds: gun.stat("burn_seconds", level),
+                stacking: gun.burn_stacking,
+            },
+            stats: PlayerStats::default(),
         }
     }
 }
@@ -591,16 +1533,26 @@ pub trait LevelableData {
     fn get_data_for_level(level: u8) -> Self;
 }
 
+/// Like [`LevelableData`], but the per-level values come from a gun prefab in the [`Guns`] registry.
+pub trait GunLevelableData {
+    fn from_gun_data(gun: &GunData, level: u8) -> Self;
+}
+
 pub fn spawn_damage_text(In(data): In<DamageTextSpawnData>, mut commands: Commands) {
+    let (text, font_size) = if data.crit {
+        (format!("{}!", data.amount), 60.0)
+    } else {
+        (data.amount.to_string(), 40.0)
+    };
     commands.spawn((
         Text2dBundle {
             text: Text::from_section(
-                data.amount.to_string(),
+                text,
                 TextStyle {
                     font: Default::default(),
                     /* Load or reference your font here */
-                    font_size: 40.0,
-                    color: Color::WHITE,
+                    font_size,
+                    color: data.damage_type.color(),
                 },
             ),
             transform: Transform::from_translation(
@@ -617,7 +1569,11 @@ pub fn spawn_flask_projectile(
     In(data): In<FlaskSpawnData>,
     mut commands: Commands,
     atlases: Res<Atlases>,
+    crit_bonus: Query<&CritBonus, With<Player>>,
 ) {
+    let stats = &data.stats;
+    let crit = crit_with_stats(data.crit, &crit_bonus, stats);
+    let damage_multiplier = stats.damage_multiplier(data.damage_type);
     let bundle = FlaskProjectileBundle {
         sprite_sheet: AnimatedSpriteBundle {
             animator: SpriteAnimator::from_anim(AnimHandle::from_index(0)),
@@ -630,7 +1586,7 @@ pub fn spawn_flask_projectile(
                 transform: Transform::from_translation(
                     data.position.extend(BACKGROUND_PROJECTILE_LAYER),
                 )
-                    .with_scale(Vec2::splat(data.scale).extend(1.0)),
+                    .with_scale(Vec2::splat(data.scale * stats.get(Stat::Area)).extend(1.0)),
                 ..default()
             },
 
@@ -654,182 +1610,180 @@ pub fn spawn_flask_projectile(
         name: Name::new("flask"),
         sensor: Default::default(),
         damage: DamageOnTouch {
-            value: data.damage,
+            value: data.damage * damage_multiplier,
+            damage_type: data.damage_type,
+            crit,
             ..default()
         },
-        lifetime: Lifetime::from_seconds(data.duration_seconds),
+        lifetime: Lifetime::from_seconds(data.duration_seconds * stats.get(Stat::Duration)),
         collisions: CollidingEntities::default(),
+        hit_cooldown: HitCooldown::from_seconds(data.hit_cooldown_seconds),
+        burn: ApplyBurnOnTouch {
+            damage_per_tick: data.burn.damage_per_tick * stats.damage_multiplier(DamageType::Fire) * stats.get(Stat::BurnDamage),
+            ..data.burn
+        },
     };
     commands.spawn(bundle);
 }
 
-pub struct FireballSpawnData {
-    pub damage: f32,
-    position: Vec3,
-    direction: Vec2,
-    pub bullet_size: f32,
-    pub pierce: u8,
-    pub bullet_speed: f32,
-}
-
+#[derive(Clone)]
 pub struct BulletSpawnData {
     pub damage: f32,
+    pub damage_type: DamageType,
+    pub crit: Crit,
     position: Vec3,
     direction: Vec2,
     pub bullet_size: f32,
+    //hitbox radius per bullet_size, most guns use PIXEL_SCALE
+    pub collider_scale: f32,
     pub pierce: u8,
+    pub bounces: u8,
     pub bullet_speed: f32,
+    pub knockback: f32,
 }
 
-//TODO: can I use one projectile spawn data and parametirize the graphic?
-//probably need to just bundle the data in each of these and keep them as separate types
-pub struct PistolBulletSpawnData {
-    pub data: BulletSpawnData,
-    pub bullet: Option<Entity>,
+impl GunLevelableData for BulletSpawnData {
+    fn from_gun_data(gun: &GunData, level: u8) -> Self {
+        Self {
+            damage: gun.stat("damage", level),
+            damage_type: gun.damage_type,
+            crit: gun.crit_for_level(level),
+            position: Default::default(),
+            direction: Default::default(),
+            bullet_size: gun.stat("bullet_size", level),
+            collider_scale: gun.stat_or("collider_scale", level, PIXEL_SCALE),
+            pierce: gun.stat("pierce", level) as u8,
+            bounces: gun.stat_or("bounces", level, 0.0) as u8,
+            bullet_speed: gun.stat("bullet_speed", level),
+            knockback: gun.stat_or("knockback", level, 0.0),
+        }
+    }
 }
 
-pub struct IceballSpawnData {
-    pub slow_amount: f32,
-    pub slow_seconds: f32,
-    pub bullet_lifetime_seconds: f32,
-    pub data: BulletSpawnData,
+/// Which layers a projectile lives on and what it can hit.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum CollisionProfile {
+    #[default]
+    PlayerProjectile,
 }
 
-impl LevelableData for IceballSpawnData {
-    fn get_data_for_level(level: u8) -> Self {
-        Self {
-            slow_amount: 0.4,
-            slow_seconds: 1.0,
-            bullet_lifetime_seconds: 2.0,
-            data: BulletSpawnData {
-                damage: 0.0,
-                position: Default::default(),
-                direction: Default::default(),
-                bullet_size: 1.0,
-                pierce: level.clamp(1, 255) - 1,
-                bullet_speed: 400.0 + (level as f32 * 10.0),
-            },
+impl CollisionProfile {
+    pub fn collision_groups(&self) -> CollisionGroups {
+        match self {
+            CollisionProfile::PlayerProjectile => CollisionGroups::new(
+                game_layer::PLAYER,
+                game_layer::GROUND | game_layer::ENEMY,
+            ),
         }
     }
 }
 
-impl LevelableData for PistolBulletSpawnData {
-    fn get_data_for_level(mut level: u8) -> Self {
-        level = level - 1;
-        Self {
-            data: BulletSpawnData {
-                damage: 1.0 + (level as f32).floor(),
-                position: Default::default(),
-                direction: Default::default(),
-                bullet_size: 1.0 + (level as f32 * 0.1_f32),
-                pierce: (level as f32 * 0.25).floor() as u8,
-                bullet_speed: 10_000.0 + (level as f32 * 10.0),
+/// Extra components a projectile carries, applied to whatever it touches.
+/// Damage is always carried through `BulletSpawnData::damage`.
+#[derive(Clone)]
+pub enum OnHitPayload {
+    Cold(ApplyColdOnTouch),
+    Burn(ApplyBurnOnTouch),
+    DamageFalloff(DamageFalloff),
+    Explosion(ExplodeOnExpire),
+}
+
+impl OnHitPayload {
+    pub fn insert_into(self, entity: &mut EntityCommands) {
+        match self {
+            OnHitPayload::Cold(cold) => {
+                entity.insert(cold);
+            }
+            OnHitPayload::Burn(burn) => {
+                entity.insert(burn);
+            }
+            OnHitPayload::DamageFalloff(falloff) => {
+                entity.insert(falloff);
+            }
+            OnHitPayload::Explosion(explosion) => {
+                entity.insert(explosion);
             }
-            ,
-            bullet: None,
         }
     }
-}
 
-impl LevelableData for FireballSpawnData {
-    fn get_data_for_level(mut level: u8) -> Self {
-        level = level - 1;
-        Self {
-            damage: 1.0 + (level as f32).floor(),
-            position: Default::default(),
-            direction: Default::default(),
-            bullet_size: 1.0 + (level as f32 * 0.1_f32),
-            pierce: (level as f32 * 0.25).floor() as u8,
-            bullet_speed: 400.0 + (level as f32 * 10.0),
+    /// Damage scales with the player's damage multipliers and blasts with area, the rest is left alone.
+    /// `damage_type` is the type of the projectile carrying the payload.
+    pub fn with_stats(self, stats: &PlayerStats, damage_type: DamageType) -> Self {
+        match self {
+            OnHitPayload::Burn(burn) => OnHitPayload::Burn(ApplyBurnOnTouch {
+                damage_per_tick: burn.damage_per_tick
+                    * stats.damage_multiplier(DamageType::Fire)
+                    * stats.get(Stat::BurnDamage),
+                ..burn
+            }),
+            OnHitPayload::DamageFalloff(falloff) => OnHitPayload::DamageFalloff(DamageFalloff {
+                base_damage: falloff.base_damage * stats.damage_multiplier(damage_type),
+                ..falloff
+            }),
+            OnHitPayload::Explosion(explosion) => OnHitPayload::Explosion(ExplodeOnExpire {
+                radius: explosion.radius * stats.get(Stat::Area),
+                damage: explosion.damage * stats.damage_multiplier(explosion.damage_type),
+                ..explosion
+            }),
+            other => other,
         }
     }
 }
 
-pub fn spawn_iceball(
-    In(data): In<IceballSpawnData>,
-    atlases: ResMut<Atlases>,
-    mut commands: Commands,
-) {
-    let speed = data.data.bullet_speed;
-    let base_size = 2.0;
-
-    let bundle = BulletBundle {
-        sprite_sheet: AnimatedSpriteBundle {
-            animator: SpriteAnimator::from_anim(AnimHandle::from_index(0)),
-            spritesheet: atlases
-                .sprite_sheets
-                .get("snowball")
-                .expect("failed to find asset for bullet!")
-                .clone(),
-            sprite_bundle: SpriteSheetBundle {
-                transform: Transform::from_translation(data.data.position).with_scale(Vec3::new(
-                    base_size * data.data.bullet_size,
-                    base_size * data.data.bullet_size,
-                    0.0,
-                )),
-                ..default()
-            },
-
-            ..Default::default()
-        },
-        physical: PhysicalBundle {
-            collider: Collider::ball(data.data.bullet_size),
-            restitution: Restitution::new(1.0),
-            velocity: Velocity {
-                linvel: data.data.direction * speed,
-                angvel: 0.0,
-            },
-            collision_layers: CollisionGroups::new(
-                game_layer::PLAYER,
-                game_layer::GROUND | game_layer::ENEMY,
-            ),
-            rigid_body: RigidBody::Dynamic,
-
-            ..default()
-        },
-        bullet: Bullet {
-            pierce: data.data.pierce,
-            ..default()
-        },
-
-        name: Name::new("snowball"),
-        sensor: Default::default(),
-        damage: DamageOnTouch {
-            value: data.data.damage,
-            ..default()
-        },
-        lifetime: Lifetime::from_seconds(data.bullet_lifetime_seconds),
-    };
-    let mut bullet = commands.spawn(bundle);
+/// Spawn data shared by every bullet-like projectile.
+/// Adding a new projectile weapon should only need a gun prefab and a fire system.
+#[derive(Clone)]
+pub struct ProjectileSpawnData {
+    pub data: BulletSpawnData,
+    pub sprite_sheet: String,
+    pub payloads: Vec<OnHitPayload>,
+    pub lifetime_seconds: f32,
+    pub collision: CollisionProfile,
+    pub homing: Option<Homing>,
+    //how far a ricochet off an enemy looks for the next one
+    pub ricochet_range: f32,
+    //stats of the ability that fired it, set by the fire system
+    pub stats: PlayerStats,
+}
 
-    bullet.insert(ApplyColdOnTouch {
-        multiplier: data.slow_amount,
-        seconds: data.slow_seconds,
-    });
+impl GunLevelableData for ProjectileSpawnData {
+    fn from_gun_data(gun: &GunData, level: u8) -> Self {
+        Self {
+            data: BulletSpawnData::from_gun_data(gun, level),
+            sprite_sheet: gun.sprite_sheet.clone(),
+            payloads: vec![],
+            lifetime_seconds: gun.stat("bullet_lifetime", level),
+            collision: CollisionProfile::PlayerProjectile,
+            homing: gun.homing_for_level(level),
+            ricochet_range: gun.stat_or("ricochet_range", level, 300.0),
+            stats: PlayerStats::default(),
+        }
+    }
 }
 
-//TODO: get rid of copy-paste
-pub fn spawn_pistol_bullet(
-    In(data): In<PistolBulletSpawnData>,
-    ice_query: Query<&ApplyColdOnTouch, With<Chambered>>,
-    atlases: ResMut<Atlases>,
+pub fn spawn_projectile(
+    In(data): In<ProjectileSpawnData>,
+    atlases: Res<Atlases>,
+    crit_bonus: Query<&CritBonus, With<Player>>,
     mut commands: Commands,
 ) {
+    let stats = &data.stats;
+    let crit = crit_with_stats(data.data.crit, &crit_bonus, stats);
+    let bullet_size = data.data.bullet_size * stats.get(Stat::Area);
     let base_size = 2.0;
     let sprite = atlases
         .sprite_sheets
-        .get("fireball")
-        .expect("failed to find asset for bullet!")
+        .get(&data.sprite_sheet)
+        .unwrap_or_else(|| panic!("failed to find asset {} for bullet!", data.sprite_sheet))
         .clone();
-    let speed = data.data.bullet_speed;
     let bundle = BulletBundle {
         sprite_sheet: AnimatedSpriteBundle {
             animator: SpriteAnimator::from_anim(AnimHandle::from_index(0)),
             spritesheet: sprite,
             sprite_bundle: SpriteSheetBundle {
                 transform: Transform::from_translation(data.data.position).with_scale(Vec3::new(
-                    base_size * data.data.bullet_size,
-                    base_size * data.data.bullet_size,
+                    base_size * bullet_size,
+                    base_size * bullet_size,
                     1.0,
                 )),
                 ..default()
@@ -838,95 +1792,91 @@ pub fn spawn_pistol_bullet(
             ..Default::default()
         },
         physical: PhysicalBundle {
-            collider: Collider::ball(PIXEL_SCALE * data.data.bullet_size),
+            collider: Collider::ball(data.data.collider_scale * bullet_size),
             restitution: Restitution::new(1.0),
             velocity: Velocity {
-                linvel: data.data.direction * speed,
+                linvel: data.data.direction * data.data.bullet_speed * stats.get(Stat::ProjectileSpeed),
                 angvel: 0.0,
             },
-            collision_layers: CollisionGroups::new(
-                game_layer::PLAYER,
-                game_layer::GROUND | game_layer::ENEMY,
-            ),
+            collision_layers: data.collision.collision_groups(),
             rigid_body: RigidBody::Dynamic,
 
             ..default()
         },
         bullet: Bullet {
             pierce: data.data.pierce,
+            bounces: data.data.bounces,
             ..default()
         },
 
-        name: Name::new("fireball"),
+        //expired_bullets_explode looks up the death animation by name
+        name: Name::new(data.sprite_sheet.clone()),
         sensor: Default::default(),
         damage: DamageOnTouch {
-            value: data.data.damage,
+            value: data.data.damage * stats.damage_multiplier(data.data.damage_type),
+            damage_type: data.data.damage_type,
+            crit,
+            knockback: data.data.knockback,
             ..default()
         },
-        lifetime: Lifetime::from_seconds(2.0),
+        lifetime: Lifetime::from_seconds(data.lifetime_seconds * stats.get(Stat::Duration)),
     };
 
-
-    let mut spawn = commands.spawn(bundle);
-    if let Ok(ice) = ice_query.get(data.bullet.unwrap()) {
-        spawn.insert(ice.clone());
+    let mut bullet = commands.spawn(bundle);
+    for payload in data.payloads {
+        payload.with_stats(stats, data.data.damage_type).insert_into(&mut bullet);
+    }
+    if let Some(homing) = data.homing {
+        bullet.insert(homing);
+    }
+    if data.data.bounces > 0 {
+        bullet.insert(Ricochet {
+            range: data.ricochet_range,
+        });
     }
 }
 
-pub fn spawn_fireball(
-    In(data): In<FireballSpawnData>,
-    atlases: ResMut<Atlases>,
-    mut commands: Commands,
-) {
-    let base_size = 2.0;
-    let sprite = atlases
-        .sprite_sheets
-        .get("fireball")
-        .expect("failed to find asset for bullet!")
-        .clone();
-    let speed = data.bullet_speed;
-    let bundle = BulletBundle {
-        sprite_sheet: AnimatedSpriteBundle {
-            animator: SpriteAnimator::from_anim(AnimHandle::from_index(0)),
-            spritesheet: sprite,
-            sprite_bundle: SpriteSheetBundle {
-                transform: Transform::from_translation(data.position).with_scale(Vec3::new(
-                    base_size * data.bullet_size,
-                    base_size * data.bullet_size,
-                    1.0,
-                )),
-                ..default()
-            },
+#[cfg(test)]
+mod tests {
+    use rand_core::SeedableRng;
 
-            ..Default::default()
-        },
-        physical: PhysicalBundle {
-            collider: Collider::ball(PIXEL_SCALE * data.bullet_size),
-            restitution: Restitution::new(1.0),
-            velocity: Velocity {
-                linvel: data.direction * speed,
-                angvel: 0.0,
-            },
-            collision_layers: CollisionGroups::new(
-                game_layer::PLAYER,
-                game_layer::GROUND | game_layer::ENEMY,
-            ),
-            rigid_body: RigidBody::Dynamic,
+    use super::*;
 
-            ..default()
-        },
-        bullet: Bullet {
-            pierce: data.pierce,
-            ..default()
-        },
+    #[test]
+    fn same_seed_rolls_the_same_crits() {
+        let mut first = WyRand::seed_from_u64(1234);
+        let mut second = WyRand::seed_from_u64(1234);
+        let first_rolls: Vec<bool> = (0..200).map(|_| roll_chance(&mut first, 0.3)).collect();
+        let second_rolls: Vec<bool> = (0..200).map(|_| roll_chance(&mut second, 0.3)).collect();
+        assert_eq!(first_rolls, second_rolls);
+        assert!(first_rolls.contains(&true) && first_rolls.contains(&false));
+    }
 
-        name: Name::new("fireball"),
-        sensor: Default::default(),
-        damage: DamageOnTouch {
-            value: data.damage,
-            ..default()
-        },
-        lifetime: Lifetime::from_seconds(2.0),
-    };
-    commands.spawn(bundle);
+    //always rolls the highest value, which used to miss a guaranteed crit
+    struct MaxRoll;
+
+    impl RngCore for MaxRoll {
+        fn next_u32(&mut self) -> u32 {
+            u32::MAX
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            u64::MAX
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            dest.fill(u8::MAX);
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn guaranteed_crit_always_lands() {
+        assert!(roll_chance(&mut MaxRoll, 1.0));
+        assert!(!roll_chance(&mut MaxRoll, 0.0));
+    }
 }
@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::components::Player;
+use crate::constants::IMPLANT_SLOTS;
+use crate::systems::modifiers::{StatModifier, StatModifiers};
+use crate::systems::synergies::{Tag, Tags};
+
+/// A strong upgrade that comes with a downside. Implants don't level up,
+/// installing one takes an implant slot for the rest of the run.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ImplantData {
+    pub name: String,
+    pub description: String,
+    pub upsides: Vec<StatModifier>,
+    pub downsides: Vec<StatModifier>,
+    #[serde(default)]
+    pub tags: Vec<Tag>,
+}
+
+impl ImplantData {
+    pub fn modifiers(&self) -> StatModifiers {
+        StatModifiers {
+            modifiers: self.upsides.iter().chain(self.downsides.iter()).copied().collect(),
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct Implants {
+    //keyed by file name, eg. prefabs/implants/third_arm.yaml -> third_arm
+    pub datas: HashMap<String, ImplantData>,
+}
+
+impl Implants {
+    pub fn get(&self, implant: &str) -> &ImplantData {
+        self.datas
+            .get(implant)
+            .unwrap_or_else(|| panic!("implant {} not found!", implant))
+    }
+
+    /// Implants that aren't installed yet, if there's a free slot for them.
+    pub fn available(&self, installed: &[&Implant]) -> Vec<String> {
+        if installed.len() >= IMPLANT_SLOTS {
+            return vec![];
+        }
+        self.datas
+            .keys()
+            .filter(|id| !installed.iter().any(|implant| &implant.id == *id))
+            .cloned()
+            .collect()
+    }
+}
+
+/// An installed implant, child of the player.
+#[derive(Component, Clone, Debug)]
+pub struct Implant {
+    pub id: String,
+}
+
+#[derive(Event)]
+pub struct InstallImplantEvent {
+    pub implant: String,
+}
+
+pub fn install_implants(
+    mut events: EventReader<InstallImplantEvent>,
+    implants: Res<Implants>,
+    installed: Query<&Implant>,
+    player: Query<Entity, With<Player>>,
+    mut commands: Commands,
+) {
+    let Ok(player) = player.get_single() else {
+        return;
+    };
+    let mut installed_count = installed.iter().count();
+    for event in events.read() {
+        if installed_count >= IMPLANT_SLOTS {
+            warn!("No free implant slot for {}", event.implant);
+            continue;
+        }
+        let data = implants.get(&event.implant);
+        info!("Installing implant {}", data.name);
+        commands
+            .spawn((
+                Name::new(data.name.clone()),
+                Implant {
+                    id: event.implant.clone(),
+                },
+                data.modifiers(),
+                Tags {
+                    tags: data.tags.clone(),
+                },
+            ))
+            .set_parent(player);
+        installed_count += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn available_skips_installed_implants_and_stops_when_slots_are_full() {
+        let implants = Implants {
+            datas: HashMap::from([
+                ("third_arm".to_string(), serde_yaml::from_str(include_str!("../../assets/prefabs/implants/third_arm.yaml")).unwrap()),
+                ("oil_reservoir".to_string(), serde_yaml::from_str(include_str!("../../assets/prefabs/implants/oil_reservoir.yaml")).unwrap()),
+                ("dermal_plating".to_string(), serde_yaml::from_str(include_str!("../../assets/prefabs/implants/dermal_plating.yaml")).unwrap()),
+            ]),
+        };
+        assert_eq!(implants.available(&[]).len(), 3);
+
+        let third_arm = Implant { id: "third_arm".to_string() };
+        let mut available = implants.available(&[&third_arm]);
+        available.sort();
+        assert_eq!(available, vec!["dermal_plating".to_string(), "oil_reservoir".to_string()]);
+
+        let oil_reservoir = Implant { id: "oil_reservoir".to_string() };
+        assert_eq!(IMPLANT_SLOTS, 2);
+        assert!(implants.available(&[&third_arm, &oil_reservoir]).is_empty());
+    }
+}
@@ -4,11 +4,12 @@ use bevy::input::mouse::MouseButtonInput;
 use bevy::math::{Vec2Swizzles, Vec3Swizzles};
 use bevy::prelude::{Camera, GlobalTransform, KeyCode, Query, Res, ResMut, Resource, Transform, Vec2, Window, With};
 use bevy::window::PrimaryWindow;
-use crate::components::{DashAbility, Dashing, Player, Reloadable, Reloading};
+use crate::components::{Ammo, DashAbility, Dashing, Jammed, Player, Reloadable, Reloading};
 use bevy::prelude::*;
 use bevy::prelude::KeyCode::KeyR;
 use bevy::time::Timer;
-use crate::systems::guns::ShootEvent;
+use crate::systems::guns::{ActiveReloadEvent, ShootEvent};
+use crate::systems::status_effects::ApplyStatusEvent;
 
 /// We will store the world position of the mouse cursor here.
 #[derive(Resource, Default)]
@@ -51,16 +52,33 @@ pub fn get_aim_direction(
 pub fn input_reload_gun_system(
     keyboard_input: Res<ButtonInput<KeyCode>>,
     mouse_input: Res<ButtonInput<MouseButton>>,
-query : Query<(Entity, &Reloadable)>,
+query : Query<(Entity, &Reloadable, &Ammo, Option<&Children>, Has<Reloading>, Has<Jammed>)>,
     mut commands: Commands,
-    mut shoot_events: EventWriter<ShootEvent>
+    mut shoot_events: EventWriter<ShootEvent>,
+    mut active_reload_events: EventWriter<ActiveReloadEvent>,
 ) {
-    if keyboard_input.pressed(RELOAD_KEY) {
-        for (entity, gun) in query.iter() {
-         commands.entity(entity).insert(Reloading{ timer: Timer::from_seconds(gun.reload_seconds_per_bullet, TimerMode::Repeating) });
+    if keyboard_input.just_pressed(RELOAD_KEY) {
+        for (entity, gun, ammo, children, reloading, jammed) in query.iter() {
+            if jammed {
+                continue;
+            }
+            if reloading {
+                //second press, guns that don't support active reload just ignore it
+                active_reload_events.send(ActiveReloadEvent(entity));
+                continue;
+            }
+            let loaded = children.map_or(0, |bullets| bullets.len()) as u16;
+            let missing = ammo.max_amount.saturating_sub(loaded);
+            commands.entity(entity).insert(Reloading {
+                timer: Timer::from_seconds(gun.reload_seconds_per_bullet, TimerMode::Repeating),
+                total: Timer::from_seconds(gun.reload_seconds_per_bullet * missing as f32, TimerMode::Once),
+            });
         }
     } else if mouse_input.pressed(SHOOT_BUTTON) {
-        for (entity, gun) in query.iter() {
+        for (entity, gun, _, _, _, jammed) in query.iter() {
+            if jammed {
+                continue;
+            }
             shoot_events.send(ShootEvent(entity));
         }
     }
@@ -71,7 +89,7 @@ pub fn input_dash_system(keyboard_input: Res<ButtonInput<KeyCode>>,
                          query : Query<(Entity, &Player), Without<Dashing>>,
                          mut ability_query: Query<&mut DashAbility>,
                          time: Res<Time>,
-                         mut commands: Commands){
+                         mut status_writer: EventWriter<ApplyStatusEvent<Dashing>>){
     let mut dash = ability_query.single_mut();
     dash.cooldown.tick(time.delta());
     if(!dash.cooldown.finished()){
@@ -80,7 +98,7 @@ pub fn input_dash_system(keyboard_input: Res<ButtonInput<KeyCode>>,
     if keyboard_input.pressed(DASH_KEY) {
         info!("dash started!");
         for (entity, gun) in query.iter() {
-            commands.entity(entity).insert(Dashing{ timer: Timer::from_seconds(0.25_f32, TimerMode::Once) });
+            status_writer.send(ApplyStatusEvent::new(entity, Dashing{ timer: Timer::from_seconds(0.25_f32, TimerMode::Once) }));
 
         }
         dash.cooldown.reset();
@@ -0,0 +1,380 @@
+use bevy::prelude::*;
+
+use crate::components::{AbilityLevel, Ammo, ChainLightningGun, Cooldown, FireBallGun, Flask, IceBallGun, LoadsSpecialRounds, ManaCost, OrbitGun, PassiveCritBonus, PassiveMoveSpeedMultiplier, PassiveXPMultiplier, PistolGun, Player, Reloadable, ShotgunGun, SniperGun, SpecialAmmoQueue, SummonMunition, Tome};
+use crate::constants::MAX_ABILITY_LEVEL;
+use crate::initialization::load_prefabs::GunId;
+use crate::systems::guns::chamber_bullet;
+use crate::systems::modifiers::{AbilityStats, LevelledModifiers, PlayerStats, Stat, StatModifier, StatModifiers};
+use crate::systems::synergies::{Tag, Tags};
+
+//loadout
+//1.. abilities don't exist until they're picked on level up, learn_abilities spawns them
+//2.. every ability belongs to a category, and each category has a limited number of slots
+//3.. slot counts are player stats, so passives, implants and the tome can change them
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AbilityCategory {
+    Weapon,
+    Spell,
+    Passive,
+}
+
+impl AbilityCategory {
+    pub fn slot_stat(&self) -> Stat {
+        match self {
+            AbilityCategory::Weapon => Stat::WeaponSlots,
+            AbilityCategory::Spell => Stat::SpellSlots,
+            AbilityCategory::Passive => Stat::PassiveSlots,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            AbilityCategory::Weapon => "Weapon",
+            AbilityCategory::Spell => "Spell",
+            AbilityCategory::Passive => "Passive",
+        }
+    }
+}
+
+/// Every ability that can be learned on level up. Lives on the spawned ability too,
+/// so learned abilities aren't offered again and take up their slot.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AbilityKind {
+    Pistol,
+    Shotgun,
+    Sniper,
+    Molotov,
+    Tome,
+    Fireball,
+    Snowball,
+    Orbit,
+    ChainLightning,
+    SummonMunition,
+    MoveSpeed,
+    XPBonus,
+    Precision,
+    Might,
+    Area,
+    Haste,
+    Velocity,
+    Duration,
+    Duplicator,
+    Luck,
+    Meditation,
+}
+
+impl AbilityKind {
+    pub const ALL: [AbilityKind; 21] = [
+        AbilityKind::Pistol,
+        AbilityKind::Shotgun,
+        AbilityKind::Sniper,
+        AbilityKind::Molotov,
+        AbilityKind::Tome,
+        AbilityKind::Fireball,
+        AbilityKind::Snowball,
+        AbilityKind::Orbit,
+        AbilityKind::ChainLightning,
+        AbilityKind::SummonMunition,
+        AbilityKind::MoveSpeed,
+        AbilityKind::XPBonus,
+        AbilityKind::Precision,
+        AbilityKind::Might,
+        AbilityKind::Area,
+        AbilityKind::Haste,
+        AbilityKind::Velocity,
+        AbilityKind::Duration,
+        AbilityKind::Duplicator,
+        AbilityKind::Luck,
+        AbilityKind::Meditation,
+    ];
+
+    pub fn category(&self) -> AbilityCategory {
+        match self {
+            AbilityKind::Pistol
+            | AbilityKind::Shotgun
+            | AbilityKind::Sniper
+            | AbilityKind::Tome => AbilityCategory::Weapon,
+            AbilityKind::Molotov
+            | AbilityKind::Fireball
+            | AbilityKind::Snowball
+            | AbilityKind::Orbit
+            | AbilityKind::ChainLightning
+            | AbilityKind::SummonMunition => AbilityCategory::Spell,
+            _ => AbilityCategory::Passive,
+        }
+    }
+
+    /// Also the [`Name`] of the spawned ability, evolution recipes match on it.
+    pub fn name(&self) -> &'static str {
+        match self {
+            AbilityKind::Pistol => "Pistol",
+            AbilityKind::Shotgun => "Shotgun",
+            AbilityKind::Sniper => "Sniper",
+            AbilityKind::Molotov => "Molotov",
+            AbilityKind::Tome => "Tome",
+            AbilityKind::Fireball => "Fireball",
+            AbilityKind::Snowball => "Snowball",
+            AbilityKind::Orbit => "Orbit",
+            AbilityKind::ChainLightning => "Chain Lightning",
+            AbilityKind::SummonMunition => "Summon Munition",
+            AbilityKind::MoveSpeed => "Move Speed",
+            AbilityKind::XPBonus => "XP Bonus",
+            AbilityKind::Precision => "Precision",
+            AbilityKind::Might => "Might",
+            AbilityKind::Area => "Area",
+            AbilityKind::Haste => "Haste",
+            AbilityKind::Velocity => "Velocity",
+            AbilityKind::Duration => "Duration",
+            AbilityKind::Duplicator => "Duplicator",
+            AbilityKind::Luck => "Luck",
+            AbilityKind::Meditation => "Meditation",
+        }
+    }
+
+    //shown on the level up button before the ability has a level description of its own
+    pub fn blurb(&self) -> &'static str {
+        match self {
+            AbilityKind::Pistol => "High damage, reload it yourself.",
+            AbilityKind::Shotgun => "Short range, wide blast.",
+            AbilityKind::Sniper => "Piercing shot that hits instantly.",
+            AbilityKind::Molotov => "Leaves a burning pool. Costs mana.",
+            AbilityKind::Tome => "Uses a hand. Grants spell slots.",
+            AbilityKind::Fireball => "Burns what it hits. Costs mana.",
+            AbilityKind::Snowball => "Slows what it hits. Costs mana.",
+            AbilityKind::Orbit => "Fireballs that circle you.",
+            AbilityKind::ChainLightning => "Jumps between enemies.",
+            AbilityKind::SummonMunition => "Reloads every weapon. Costs mana.",
+            AbilityKind::MoveSpeed => "Move faster.",
+            AbilityKind::XPBonus => "Gain more XP.",
+            AbilityKind::Precision => "Crit more often, and harder.",
+            AbilityKind::Might => "Deal more damage.",
+            AbilityKind::Area => "Bigger projectiles, pools and blasts.",
+            AbilityKind::Haste => "Shorter cooldowns.",
+            AbilityKind::Velocity => "Faster projectiles.",
+            AbilityKind::Duration => "Projectiles and pools last longer.",
+            AbilityKind::Duplicator => "Fire extra projectiles.",
+            AbilityKind::Luck => "Crit more often.",
+            AbilityKind::Meditation => "More mana, and it comes back faster.",
+        }
+    }
+
+    /// What synergies see this ability as, passives don't have any.
+    pub fn tags(&self) -> Vec<Tag> {
+        match self {
+            AbilityKind::Pistol | AbilityKind::Shotgun | AbilityKind::Sniper => vec![Tag::Metal],
+            AbilityKind::Molotov => vec![Tag::Fire],
+            AbilityKind::Tome | AbilityKind::ChainLightning => vec![Tag::Magic],
+            AbilityKind::Fireball | AbilityKind::Orbit => vec![Tag::Magic, Tag::Fire],
+            AbilityKind::Snowball => vec![Tag::Magic, Tag::Ice],
+            AbilityKind::SummonMunition => vec![Tag::Magic, Tag::Metal],
+            _ => vec![],
+        }
+    }
+
+    fn stat_modifiers(&self) -> Vec<StatModifier> {
+        match self {
+            AbilityKind::MoveSpeed => vec![StatModifier::add(Stat::MoveSpeed, 0.1)],
+            AbilityKind::XPBonus => vec![StatModifier::add(Stat::XPGain, 0.1)],
+            AbilityKind::Might => vec![StatModifier::add(Stat::Might, 0.1)],
+            AbilityKind::Area => vec![StatModifier::add(Stat::Area, 0.1)],
+            AbilityKind::Haste => vec![StatModifier::add(Stat::Cooldown, -0.08)],
+            AbilityKind::Velocity => vec![StatModifier::add(Stat::ProjectileSpeed, 0.1)],
+            AbilityKind::Duration => vec![StatModifier::add(Stat::Duration, 0.1)],
+            AbilityKind::Duplicator => vec![StatModifier::add(Stat::Amount, 0.5)],
+            AbilityKind::Luck => vec![StatModifier::add(Stat::Luck, 0.1)],
+            AbilityKind::Meditation => vec![
+                StatModifier::add(Stat::MaxMana, 0.15),
+                StatModifier::add(Stat::ManaRegen, 0.2),
+            ],
+            _ => vec![],
+        }
+    }
+}
+
+/// Abilities that aren't learned yet and still have a free slot in their category.
+pub fn available_to_learn(learned: &[AbilityKind], stats: &PlayerStats) -> Vec<AbilityKind> {
+    AbilityKind::ALL
+        .iter()
+        .filter(|kind| !learned.contains(kind))
+        .filter(|kind| {
+            let category = kind.category();
+            let used = learned.iter().filter(|learned| learned.category() == category).count();
+            used < stats.slots(category.slot_stat())
+        })
+        .copied()
+        .collect()
+}
+
+#[derive(Event)]
+pub struct LearnAbilityEvent {
+    pub ability: AbilityKind,
+}
+
+pub fn learn_abilities(
+    mut events: EventReader<LearnAbilityEvent>,
+    player: Query<Entity, With<Player>>,
+    learned: Query<&AbilityKind>,
+    mut special_ammo: Query<&mut SpecialAmmoQueue>,
+    mut commands: Commands,
+) {
+    let Ok(player) = player.get_single() else {
+        return;
+    };
+    for event in events.read() {
+        if learned.iter().any(|kind| *kind == event.ability) {
+            warn!("{} is already learned", event.ability.name());
+            continue;
+        }
+        info!("Learning {}", event.ability.name());
+        spawn_ability(&mut commands, player, event.ability, &mut special_ammo);
+    }
+}
+
+pub fn spawn_ability(
+    commands: &mut Commands,
+    player: Entity,
+    kind: AbilityKind,
+    special_ammo: &mut Query<&mut SpecialAmmoQueue>,
+) -> Entity {
+    let mut ability = commands.spawn((
+        kind,
+        Name::new(kind.name()),
+        AbilityLevel {
+            level: 1,
+            ..default()
+        },
+        Tags { tags: kind.tags() },
+        AbilityStats::default(),
+    ));
+    ability.set_parent(player);
+    //guns that reload, and whether they take special rounds
+    let mut magazine: Option<(Ammo, bool)> = None;
+    match kind {
+        AbilityKind::Pistol => {
+            ability.insert((
+                Cooldown::with_cooldown(1000),
+                PistolGun {},
+                GunId("pistol".to_string()),
+                SpatialBundle { ..default() },
+                Reloadable { reload_seconds_per_bullet: 0.25_f32 },
+                LoadsSpecialRounds {},
+            ));
+            magazine = Some((Ammo { max_amount: 6 }, true));
+        }
+        AbilityKind::Shotgun => {
+            ability.insert((
+                Cooldown::with_cooldown(800),
+                ShotgunGun {},
+                GunId("shotgun".to_string()),
+                SpatialBundle { ..default() },
+                Reloadable { reload_seconds_per_bullet: 0.5_f32 },
+            ));
+            magazine = Some((Ammo { max_amount: 2 }, false));
+        }
+        AbilityKind::Sniper => {
+            ability.insert((
+                Cooldown::with_cooldown(1200),
+                SniperGun {},
+                GunId("sniper".to_string()),
+                SpatialBundle { ..default() },
+                Reloadable { reload_seconds_per_bullet: 1.5_f32 },
+            ));
+            magazine = Some((Ammo { max_amount: 3 }, false));
+        }
+        AbilityKind::Molotov => {
+            ability.insert((
+                Cooldown::with_cooldown(0),
+                Flask {},
+                GunId("molotov".to_string()),
+                SpatialBundle { ..default() },
+                ManaCost { amount: 20.0 },
+            ));
+        }
+        AbilityKind::Tome => {
+            ability.insert((Tome {}, StatModifiers::default()));
+        }
+        AbilityKind::Fireball => {
+            ability.insert((
+                Cooldown::with_cooldown(500),
+                FireBallGun {},
+                GunId("fireball".to_string()),
+                SpatialBundle { ..default() },
+                ManaCost { amount: 6.0 },
+            ));
+        }
+        AbilityKind::Snowball => {
+            ability.insert((
+                Cooldown::with_cooldown(900),
+                IceBallGun {},
+                GunId("snowball".to_string()),
+                SpatialBundle { ..default() },
+                ManaCost { amount: 10.0 },
+            ));
+        }
+        AbilityKind::Orbit => {
+            ability.insert((Cooldown::with_cooldown(0), OrbitGun {}, GunId("orbit".to_string())));
+        }
+        AbilityKind::ChainLightning => {
+            ability.insert((
+                Cooldown::with_cooldown(1500),
+                ChainLightningGun {},
+                GunId("chain_lightning".to_string()),
+                SpatialBundle { ..default() },
+            ));
+        }
+        AbilityKind::SummonMunition => {
+            ability.insert((
+                Cooldown::with_cooldown(0),
+                SummonMunition {},
+                ManaCost { amount: 30.0 },
+                GunId("summon_munition".to_string()),
+            ));
+        }
+        AbilityKind::Precision => {
+            ability.insert(PassiveCritBonus {});
+        }
+        AbilityKind::MoveSpeed => {
+            ability.insert(PassiveMoveSpeedMultiplier { ..default() });
+        }
+        AbilityKind::XPBonus => {
+            ability.insert(PassiveXPMultiplier {});
+        }
+        _ => {}
+    }
+    let per_level = kind.stat_modifiers();
+    if !per_level.is_empty() {
+        ability.insert((LevelledModifiers { per_level }, StatModifiers::default()));
+    }
+    if let Some((ammo, _)) = magazine {
+        ability.insert(ammo);
+    }
+    let ability = ability.id();
+
+    //new guns come loaded
+    if let Some((ammo, loads_special_rounds)) = magazine {
+        for _ in 0..ammo.max_amount {
+            chamber_bullet(commands, ability, loads_special_rounds, special_ammo);
+        }
+    }
+    ability
+}
+
+/// Two spell slots right away, cooldown reduction every level after that,
+/// and one more spell slot at max level.
+pub fn tome_modifiers(level: u8) -> Vec<StatModifier> {
+    if level == 0 {
+        return vec![];
+    }
+    let spell_slots = if level >= MAX_ABILITY_LEVEL { 3.0 } else { 2.0 };
+    vec![
+        StatModifier::add(Stat::SpellSlots, spell_slots),
+        StatModifier::add(Stat::Cooldown, -0.05 * (level - 1) as f32),
+    ]
+}
+
+pub fn apply_tome(mut query: Query<(&AbilityLevel, &mut StatModifiers), (With<Tome>, Changed<AbilityLevel>)>) {
+    for (ability, mut modifiers) in query.iter_mut() {
+        modifiers.modifiers = tome_modifiers(ability.level);
+    }
+}
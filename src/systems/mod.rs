@@ -1,10 +1,17 @@
 pub mod animation;
 pub mod audio;
 pub mod dev;
+pub mod evolution;
 pub mod guns;
+pub mod implants;
+pub mod loadout;
+pub mod modifiers;
 pub mod movement;
 pub mod spawning;
 pub mod stats;
+pub mod status_effects;
+pub mod synergies;
+pub mod targeting;
 pub mod ui;
 
 pub mod input;
\ No newline at end of file
@@ -0,0 +1,346 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::components::{AbilityLevel, BaseMana, BaseMaxHealth, Crit, CritBonus, DamageType, Health, Mana, MaxHealth, Player};
+
+//stats
+//1.. passives, implants and buffs carry a StatModifiers component
+//2.. resolve_player_stats folds every StatModifiers into the player's PlayerStats
+//3.. anything that spawns or ticks player stuff reads PlayerStats instead of looking for passives
+
+#[derive(Serialize, Deserialize, Reflect, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Stat {
+    //damage
+    Might,
+    //size of projectiles, pools and blasts
+    Area,
+    //lower is faster
+    Cooldown,
+    ProjectileSpeed,
+    //how long projectiles and pools last
+    Duration,
+    //extra projectiles
+    Amount,
+    //crit chance
+    Luck,
+    MoveSpeed,
+    #[serde(rename = "xp_gain")]
+    XPGain,
+    MaxHealth,
+    MaxMana,
+    ManaRegen,
+    //only fire damage over time, on top of might
+    BurnDamage,
+    MagicDamage,
+    PhysicalDamage,
+    //chance for the player to ignore a hit completely
+    Dodge,
+    WeaponSlots,
+    SpellSlots,
+    PassiveSlots,
+}
+
+impl Stat {
+    pub const ALL: [Stat; 19] = [
+        Stat::Might,
+        Stat::Area,
+        Stat::Cooldown,
+        Stat::ProjectileSpeed,
+        Stat::Duration,
+        Stat::Amount,
+        Stat::Luck,
+        Stat::MoveSpeed,
+        Stat::XPGain,
+        Stat::MaxHealth,
+        Stat::MaxMana,
+        Stat::ManaRegen,
+        Stat::BurnDamage,
+        Stat::MagicDamage,
+        Stat::PhysicalDamage,
+        Stat::Dodge,
+        Stat::WeaponSlots,
+        Stat::SpellSlots,
+        Stat::PassiveSlots,
+    ];
+
+    /// What the stat is with no modifiers at all.
+    pub fn base(&self) -> f32 {
+        match self {
+            Stat::Amount | Stat::Dodge => 0.0,
+            Stat::WeaponSlots => 3.0,
+            Stat::SpellSlots => 2.0,
+            Stat::PassiveSlots => 6.0,
+            _ => 1.0,
+        }
+    }
+
+    /// Stats that count things instead of scaling them.
+    pub fn is_count(&self) -> bool {
+        matches!(self, Stat::Amount | Stat::WeaponSlots | Stat::SpellSlots | Stat::PassiveSlots)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Stat::Might => "Might",
+            Stat::Area => "Area",
+            Stat::Cooldown => "Cooldown",
+            Stat::ProjectileSpeed => "Projectile Speed",
+            Stat::Duration => "Duration",
+            Stat::Amount => "Amount",
+            Stat::Luck => "Luck",
+            Stat::MoveSpeed => "Move Speed",
+            Stat::XPGain => "XP Gain",
+            Stat::MaxHealth => "Max Health",
+            Stat::MaxMana => "Max Mana",
+            Stat::ManaRegen => "Mana Regen",
+            Stat::BurnDamage => "Burn Damage",
+            Stat::MagicDamage => "Magic Damage",
+            Stat::PhysicalDamage => "Physical Damage",
+            Stat::Dodge => "Dodge",
+            Stat::WeaponSlots => "Weapon Slots",
+            Stat::SpellSlots => "Spell Slots",
+            Stat::PassiveSlots => "Passive Slots",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ModifierKind {
+    //summed up and added to the base
+    Add,
+    //multiplied together and applied after every Add
+    Multiply,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct StatModifier {
+    pub stat: Stat,
+    pub kind: ModifierKind,
+    pub value: f32,
+}
+
+impl StatModifier {
+    pub fn add(stat: Stat, value: f32) -> Self {
+        Self {
+            stat,
+            kind: ModifierKind::Add,
+            value,
+        }
+    }
+
+    pub fn multiply(stat: Stat, value: f32) -> Self {
+        Self {
+            stat,
+            kind: ModifierKind::Multiply,
+            value,
+        }
+    }
+
+    /// The same modifier stacked `level` times.
+    pub fn for_level(&self, level: u8) -> Self {
+        let value = match self.kind {
+            ModifierKind::Add => self.value * level as f32,
+            ModifierKind::Multiply => self.value.powi(level as i32),
+        };
+        Self { value, ..*self }
+    }
+
+    pub fn display_value(&self) -> String {
+        match self.kind {
+            ModifierKind::Add if self.stat.is_count() => format!("{:+}", self.value),
+            ModifierKind::Add => format!("{:+.0}%", self.value * 100.0),
+            ModifierKind::Multiply => format!("x{:.2}", self.value),
+        }
+    }
+}
+
+impl Display for StatModifier {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.display_value(), self.stat.label())
+    }
+}
+
+/// Every one of these in the world counts towards the player's stats,
+/// only the player has passives for now.
+#[derive(Component, Clone, Debug, Default)]
+pub struct StatModifiers {
+    pub modifiers: Vec<StatModifier>,
+}
+
+/// For passives that grant the same modifiers again every level.
+#[derive(Component, Clone, Debug, Default)]
+pub struct LevelledModifiers {
+    pub per_level: Vec<StatModifier>,
+}
+
+impl LevelledModifiers {
+    pub fn for_level(&self, level: u8) -> Vec<StatModifier> {
+        self.per_level.iter().map(|modifier| modifier.for_level(level)).collect()
+    }
+}
+
+/// The player's stats with every modifier applied.
+#[derive(Component, Clone, Debug)]
+pub struct PlayerStats {
+    values: HashMap<Stat, f32>,
+}
+
+impl Default for PlayerStats {
+    fn default() -> Self {
+        Self::resolve(std::iter::empty())
+    }
+}
+
+impl PlayerStats {
+    pub fn resolve<'a>(modifiers: impl Iterator<Item = &'a StatModifier>) -> Self {
+        let mut added: HashMap<Stat, f32> = HashMap::new();
+        let mut multiplied: HashMap<Stat, f32> = HashMap::new();
+        for modifier in modifiers {
+            match modifier.kind {
+                ModifierKind::Add => *added.entry(modifier.stat).or_insert(0.0) += modifier.value,
+                ModifierKind::Multiply => *multiplied.entry(modifier.stat).or_insert(1.0) *= modifier.value,
+            }
+        }
+        let values = Stat::ALL
+            .iter()
+            .map(|stat| {
+                let value = (stat.base() + added.get(stat).unwrap_or(&0.0)) * multiplied.get(stat).unwrap_or(&1.0);
+                //nothing goes below zero, cooldowns can't get infinitely fast and some hits always land
+                let min = if *stat == Stat::Cooldown { 0.1 } else { 0.0 };
+                let max = if *stat == Stat::Dodge { 0.75 } else { f32::MAX };
+                (*stat, value.clamp(min, max))
+            })
+            .collect();
+        Self { values }
+    }
+
+    pub fn get(&self, stat: Stat) -> f32 {
+        self.values.get(&stat).copied().unwrap_or(stat.base())
+    }
+
+    /// Might plus whatever bonus the damage type has.
+    pub fn damage_multiplier(&self, damage_type: DamageType) -> f32 {
+        let type_multiplier = match damage_type {
+            DamageType::Physical => self.get(Stat::PhysicalDamage),
+            DamageType::Magic => self.get(Stat::MagicDamage),
+            DamageType::Fire | DamageType::Ice => 1.0,
+        };
+        self.get(Stat::Might) * type_multiplier
+    }
+
+    /// Whole extra projectiles, partial amounts don't count yet.
+    pub fn amount(&self) -> u32 {
+        self.get(Stat::Amount).floor() as u32
+    }
+
+    pub fn slots(&self, stat: Stat) -> usize {
+        self.get(stat).floor() as usize
+    }
+}
+
+/// The player's stats with the synergies of one ability on top.
+/// Weapons fire with these instead of [`PlayerStats`].
+#[derive(Component, Clone, Debug, Default)]
+pub struct AbilityStats {
+    pub stats: PlayerStats,
+}
+
+/// Crit bonus lives on the player, luck comes from the stats the weapon fires with.
+pub fn crit_with_stats(crit: Crit, bonus: &Query<&CritBonus, With<Player>>, stats: &PlayerStats) -> Crit {
+    let mut crit = bonus.get_single().map_or(crit, |bonus| crit.with_bonus(bonus));
+    crit.chance *= stats.get(Stat::Luck);
+    crit
+}
+
+pub fn apply_levelled_modifiers(
+    mut query: Query<(&LevelledModifiers, &AbilityLevel, &mut StatModifiers), Changed<AbilityLevel>>,
+) {
+    for (levelled, ability, mut modifiers) in query.iter_mut() {
+        modifiers.modifiers = levelled.for_level(ability.level);
+    }
+}
+
+pub fn resolve_player_stats(
+    modifiers: Query<&StatModifiers>,
+    changed: Query<(), Changed<StatModifiers>>,
+    mut removed: RemovedComponents<StatModifiers>,
+    mut player: Query<&mut PlayerStats, With<Player>>,
+) {
+    let removed_any = removed.read().count() > 0;
+    if changed.is_empty() && !removed_any {
+        return;
+    }
+    let Ok(mut stats) = player.get_single_mut() else {
+        return;
+    };
+    *stats = PlayerStats::resolve(modifiers.iter().flat_map(|modifiers| modifiers.modifiers.iter()));
+}
+
+/// Raising max health heals by the difference, lowering it only cuts off what's above the new max.
+pub fn apply_max_health(
+    mut player: Query<(&mut Health, &mut MaxHealth, &BaseMaxHealth, &PlayerStats), Changed<PlayerStats>>,
+) {
+    for (mut health, mut max_health, base, stats) in player.iter_mut() {
+        let new_max = base.value * stats.get(Stat::MaxHealth);
+        if new_max > max_health.value {
+            health.value += new_max - max_health.value;
+        }
+        health.value = health.value.min(new_max);
+        max_health.value = new_max;
+    }
+}
+
+/// Same as max health, a bigger pool comes filled up.
+pub fn apply_max_mana(mut player: Query<(&mut Mana, &BaseMana, &PlayerStats), Changed<PlayerStats>>) {
+    for (mut mana, base, stats) in player.iter_mut() {
+        let new_max = base.max * stats.get(Stat::MaxMana);
+        if new_max > mana.max {
+            mana.value += new_max - mana.max;
+        }
+        mana.value = mana.value.min(new_max);
+        mana.max = new_max;
+        mana.regen_per_second = base.regen_per_second * stats.get(Stat::ManaRegen);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_adds_then_multiplies_then_clamps() {
+        assert_eq!(PlayerStats::resolve(std::iter::empty()).get(Stat::WeaponSlots), Stat::WeaponSlots.base());
+
+        let modifiers = [
+            StatModifier::add(Stat::Might, 0.5),
+            StatModifier::multiply(Stat::Might, 2.0),
+            StatModifier::add(Stat::Might, 0.5),
+            StatModifier::add(Stat::Cooldown, -2.0),
+            StatModifier::add(Stat::Dodge, 2.0),
+            StatModifier::add(Stat::Area, -5.0),
+        ];
+        let stats = PlayerStats::resolve(modifiers.iter());
+        assert_eq!(stats.get(Stat::Might), 4.0);
+        //cooldowns bottom out, dodge tops out, nothing goes negative
+        assert_eq!(stats.get(Stat::Cooldown), 0.1);
+        assert_eq!(stats.get(Stat::Dodge), 0.75);
+        assert_eq!(stats.get(Stat::Area), 0.0);
+        assert_eq!(stats.get(Stat::Luck), 1.0);
+    }
+
+    #[test]
+    fn for_level_stacks_adds_linearly_and_multiplies_exponentially() {
+        let add = StatModifier::add(Stat::Area, 0.25);
+        assert_eq!(add.for_level(0).value, 0.0);
+        assert_eq!(add.for_level(3), StatModifier::add(Stat::Area, 0.75));
+
+        let multiply = StatModifier::multiply(Stat::Might, 2.0);
+        assert_eq!(multiply.for_level(0).value, 1.0);
+        assert_eq!(multiply.for_level(3), StatModifier::multiply(Stat::Might, 8.0));
+    }
+}
@@ -6,14 +6,15 @@ use bevy::prelude::{EventReader, KeyCode, Query, Res, Time, Transform, With, Wit
 use bevy_rapier2d::dynamics::Velocity;
 use bevy_rapier2d::prelude::*;
 
-use crate::components::{AbilityLevel, BaseMoveSpeed, Cold, FollowPlayer, MoveSpeed, ParentMoveSpeedMultiplier, PassiveXPMultiplier, Player, XPMultiplier, XPPickupRadius, XPVacuum, XP, Dashing};
+use crate::components::{AbilityLevel, BaseMoveSpeed, Cold, CritBonus, PassiveCritBonus, FollowPlayer, MoveSpeed, Player, XPPickupRadius, XPVacuum, XP, Dashing, KnockedBack, KnockbackResistance};
 use crate::extensions::vectors::to_vec2;
 use crate::systems::guns::LevelableData;
+use crate::systems::modifiers::{PlayerStats, Stat};
 
 pub fn set_follower_velocity(
     mut query: Query<
         (&mut Velocity, &MoveSpeed, &Transform),
-        (With<FollowPlayer>, Without<Player>),
+        (With<FollowPlayer>, Without<Player>, Without<KnockedBack>),
     >,
     player_query: Query<&mut Transform, With<Player>>,
 ) {
@@ -28,6 +29,22 @@ pub fn set_follower_velocity(
     }
 }
 
+/// Knockback fades out over its duration, resistant entities get pushed less.
+pub fn apply_knockback(
+    mut query: Query<(Entity, &mut Velocity, &KnockedBack, Option<&KnockbackResistance>)>,
+    mut commands: Commands,
+) {
+    for (entity, mut velocity, knocked_back, resistance) in query.iter_mut() {
+        let strength = 1.0 - resistance.map_or(0.0, |resistance| resistance.value);
+        if strength <= 0.0 {
+            //can't be moved, don't let it stop their steering either
+            commands.entity(entity).remove::<KnockedBack>();
+            continue;
+        }
+        velocity.linvel = knocked_back.velocity * strength * (1.0 - knocked_back.timer.fraction());
+    }
+}
+
 pub fn apply_xp_radius(
     mut modifier_query: Query<
         (Entity, &XPVacuum, &AbilityLevel, &mut Collider),
@@ -42,13 +59,13 @@ pub fn apply_xp_radius(
     }
 }
 
-pub fn apply_xp_multiplier(
-    mut modifier_query: Query<(&PassiveXPMultiplier, &AbilityLevel), Changed<AbilityLevel>>,
-    mut player_query: Query<(&mut XPMultiplier, &Player)>,
+pub fn apply_crit_bonus(
+    mut modifier_query: Query<(&PassiveCritBonus, &AbilityLevel), Changed<AbilityLevel>>,
+    mut player_query: Query<(&mut CritBonus, &Player)>,
 ) {
-    let (mut xp_multi, player) = player_query.single_mut();
+    let (mut crit_bonus, player) = player_query.single_mut();
     for (_, ability) in modifier_query.iter_mut() {
-        xp_multi.value = XPMultiplier::get_data_for_level(ability.level).value;
+        *crit_bonus = CritBonus::get_data_for_level(ability.level);
     }
 }
 
@@ -57,21 +74,14 @@ pub fn apply_move_speed_multiplier(
         Entity,
         &mut MoveSpeed,
         &BaseMoveSpeed,
-        Option<&Children>,
+        Option<&PlayerStats>,
         Option<&mut Cold>,
     )>,
-    modifier_query: Query<&ParentMoveSpeedMultiplier>,
     mut commands: Commands,
     time: Res<Time>,
 ) {
-    for (entity, mut move_speed, base_move, children_maybe, cold_maybe) in &mut parent_query {
-        let mut multiplier = 1.0;
-
-        if let Some(children) = children_maybe {
-            for modifier in modifier_query.iter_many(children) {
-                multiplier += modifier.value;
-            }
-        }
+    for (entity, mut move_speed, base_move, stats_maybe, cold_maybe) in &mut parent_query {
+        let mut multiplier = stats_maybe.map_or(1.0, |stats| stats.get(Stat::MoveSpeed));
 
         if let Some(mut cold) = cold_maybe {
             multiplier -= cold.multiplier;
@@ -112,10 +122,13 @@ pub fn _debug_collisions(mut collision_events: EventReader<CollisionEvent>) {
 // #[bevycheck::system]
 pub fn move_player(
     keyboard_input: Res<ButtonInput<KeyCode>>,
-    mut query: Query<(&mut Velocity, &MoveSpeed, Option<&Dashing>), With<Player>>,
+    mut query: Query<(&mut Velocity, &MoveSpeed, Option<&Dashing>, Has<KnockedBack>), With<Player>>,
     time: Res<Time>,
 ) {
-    let (mut velocity, move_speed, dashing) = query.single_mut();
+    let (mut velocity, move_speed, dashing, knocked_back) = query.single_mut();
+    if knocked_back {
+        return;
+    }
     let mut direction: Vec2 = Default::default();
 
     if keyboard_input.pressed(KeyCode::KeyA) {
@@ -1,24 +1,33 @@
 use std::default;
 use std::fmt::Display;
 use std::string::String;
+use std::time::Duration;
 
 use bevy::asset::Assets;
 use bevy::core::Name;
+use bevy::ecs::query::Has;
 use bevy::hierarchy::{BuildChildren, Children, Parent};
 use bevy::math::{Vec2, Vec3, Vec3Swizzles};
-use bevy::prelude::{default, Changed, Color, ColorMaterial, Commands, Entity, EventReader, Mesh, NextState, Query, ResMut, Sprite, SpriteSheetBundle, Transform, With, Without, EventWriter, Event, GlobalTransform};
-use bevy::time::{Timer, TimerMode};
+use bevy::prelude::{default, Changed, Res, Color, ColorMaterial, Commands, Entity, EventReader, Mesh, NextState, Query, ResMut, Sprite, SpriteSheetBundle, Transform, With, Without, EventWriter, Event, GlobalTransform};
+use bevy::time::{Time, Timer, TimerMode};
 use bevy_asepritesheet::animator::{AnimatedSpriteBundle, SpriteAnimator};
 use bevy_asepritesheet::sprite::Spritesheet;
 use bevy_egui::egui::debug_text::print;
+use bevy_prng::WyRand;
+use bevy_rand::prelude::GlobalEntropy;
+use rand_core::RngCore;
 use bevy_rapier2d::parry::transformation::utils::transform;
 use bevy_rapier2d::pipeline::CollisionEvent;
 use rand::Rng;
 
 use crate::bundles::{spawn_xp, CorpseBundle, CorpseSpawnData, Object, XPSpawnData};
-use crate::components::{AbilityLevel, BaseMoveSpeed, Cold, Cooldown, Enemy, FireBallGun, Flask, FollowPlayer, GainXPOnTouch, Health, IceBallGun, Lifetime, MoveSpeed, ParentMoveSpeedMultiplier, PassiveMoveSpeedMultiplier, PassiveXPMultiplier, Player, XPMultiplier, XPPickupRadius, XPVacuum, XP, PistolGun, Ammo, Reloading, Chambered, ApplyColdOnTouch};
+use crate::components::{AbilityLevel, BaseMoveSpeed, Burning, Cold, Cooldown, Enemy, FireBallGun, Flask, FollowPlayer, GainXPOnTouch, Health, IceBallGun, Lifetime, Mana, MoveSpeed, Player, XPPickupRadius, XPVacuum, XP, PistolGun, Ammo, Reloading, Chambered, ApplyColdOnTouch, ShotgunGun, SniperGun, CritBonus, PassiveCritBonus, SpecialAmmoQueue, SpecialRound, OrbitData, OrbitGun, ChainLightningGun, SummonMunition, SummonMunitionData, Tome};
 use crate::extensions::spew_extensions::{Spawn, Spawner};
-use crate::systems::guns::{Damaged, FireballSpawnData, FlaskSpawnData, IceballSpawnData, LevelableData, ParticleSpawnData, PistolBulletSpawnData};
+use crate::initialization::load_prefabs::{GunId, Guns};
+use crate::systems::evolution::{Evolutions, Evolved};
+use crate::systems::guns::{BulletSpawnData, Damaged, FlaskSpawnData, GunLevelableData, LevelableData, ParticleSpawnData, ProjectileSpawnData};
+use crate::systems::loadout::tome_modifiers;
+use crate::systems::modifiers::{LevelledModifiers, PlayerStats, Stat};
 use crate::AppState;
 use bevy::log::*;
 use tracing::event;
@@ -42,7 +51,10 @@ pub fn snowball_reload_bullet_if_killed_enemy_is_frozen(
     mut event_reader: EventReader<DeathEvent>,
     mut snowball_gun: Query<(&IceBallGun, &AbilityLevel)>,
 ) {
-    let (gun, ability) = snowball_gun.single();
+    //snowball only exists once it's learned
+    let Ok((gun, ability)) = snowball_gun.get_single() else {
+        return;
+    };
     if ability.level == 0{
     return;}
     //add a bullet to chamber without triggering a reload
@@ -57,12 +69,31 @@ pub fn snowball_reload_bullet_if_killed_enemy_is_frozen(
             info!("Cold enemy died.");
             for (entity, _1, _2) in query.iter() {
                 info!("Spawning bullet in chamber.");
-                commands.spawn((Chambered {}, ApplyColdOnTouch { multiplier: 1.0, seconds: 2.0 })).set_parent(entity);
+                commands.spawn((Chambered {}, SpecialRound::Frozen)).set_parent(entity);
             }
         }
     }
 }
 
+/// Kills that finish off burning enemies feed the revolver's special ammo queue.
+pub fn queue_special_rounds_on_kill(
+    mut event_reader: EventReader<DeathEvent>,
+    status_query: Query<(Has<Burning>, Has<Cold>), With<Enemy>>,
+    mut special_ammo: Query<&mut SpecialAmmoQueue>,
+) {
+    let Ok(mut queue) = special_ammo.get_single_mut() else {
+        return;
+    };
+    for x in event_reader.read() {
+        match status_query.get(x.0) {
+            //burning and frozen at the same time is a bit too much for anyone
+            Ok((true, true)) => queue.push(SpecialRound::Explosive),
+            Ok((true, false)) => queue.push(SpecialRound::Incendiary),
+            _ => {}
+        }
+    }
+}
+
 pub fn die_at_zero_health(
     query: Query<(Entity, &Enemy, &Health, &Transform, &Name, &Sprite)>,
     mut spawner: Spawner<CorpseSpawnData>,
@@ -93,15 +124,12 @@ pub fn die_at_zero_health(
     }
 }
 
-pub fn update_move_speed_from_passive(
-    mut abilities: Query<(
-        &AbilityLevel,
-        &PassiveMoveSpeedMultiplier,
-        &mut ParentMoveSpeedMultiplier,
-    )>,
-) {
-    for (ability, _, mut parent_move_speed) in abilities.iter_mut() {
-        parent_move_speed.value = 0.10 * ability.level as f32;
+pub fn regenerate_mana(mut query: Query<&mut Mana>, time: Res<Time>) {
+    for mut mana in query.iter_mut() {
+        if mana.value >= mana.max {
+            continue;
+        }
+        mana.value = (mana.value + mana.regen_per_second * time.delta_seconds()).min(mana.max);
     }
 }
 
@@ -139,6 +167,36 @@ pub fn cold_enemies_spawn_particles(
     }
 }
 
+pub fn burning_objects_are_red(mut sprites: Query<&mut Sprite, With<Burning>>) {
+    for mut sprite in sprites.iter_mut() {
+        sprite.color = Color::rgb(1.0, 0.4, 0.2);
+    }
+}
+
+pub fn burning_enemies_spawn_particles(
+    mut sprites: Query<(Entity, &Enemy), With<Burning>>,
+    mut spawner: Spawner<ParticleSpawnData>,
+    mut rng: ResMut<GlobalEntropy<WyRand>>,
+) {
+    for (entity, enemy) in sprites.iter() {
+        //flames drift upwards from somewhere around the enemy's feet
+        let mut roll = |min: f32, max: f32| min + (max - min) * (rng.next_u32() as f32 / u32::MAX as f32);
+        let position = Vec2::new(roll(-15.0, 15.0), roll(-10.0, 20.0));
+        spawner.spawn(
+            Object::Particle,
+            ParticleSpawnData {
+                parent: Some(entity),
+                sprite_sheet: "fireball".to_string(),
+                color: Color::rgb(1.0, 0.5, 0.1),
+                animation: "Idle".to_string(),
+                lifetime: Lifetime::from_seconds(0.4),
+                position,
+                scale: Vec2::new(0.3, 0.3),
+            },
+        )
+    }
+}
+
 pub fn reset_sprite_color(mut sprites: Query<&mut Sprite>) {
     for mut sprite in sprites.iter_mut() {
         sprite.color = Color::default();
@@ -183,20 +241,22 @@ turn ability into new ability
 //         }
 //     }
 // }
-pub fn update_level_descriptions_move_speed(
-    mut abilities: Query<(&mut AbilityLevel, &PassiveMoveSpeedMultiplier), Changed<AbilityLevel>>,
+//every passive that only grants stat modifiers describes itself the same way
+pub fn update_level_descriptions_modifiers(
+    mut abilities: Query<(&mut AbilityLevel, &Name, &LevelledModifiers), Changed<AbilityLevel>>,
 ) {
-    for (mut ability, _flask) in abilities.iter_mut() {
-        let current_level = PassiveMoveSpeedMultiplier::get_data_for_level(ability.level);
-        let next_level = PassiveMoveSpeedMultiplier::get_data_for_level(ability.level + 1);
-        let mut description = "Upgrade Move Speed".to_string();
-
-        push_stat_block(
-            &mut description,
-            "Move Speed",
-            current_level.value,
-            next_level.value,
-        );
+    for (mut ability, name, levelled) in abilities.iter_mut() {
+        let current_level = levelled.for_level(ability.level);
+        let next_level = levelled.for_level(ability.level + 1);
+        let mut description = name.to_string();
+        for (current, next) in current_level.iter().zip(next_level.iter()) {
+            push_stat_block(
+                &mut description,
+                current.stat.label(),
+                current.display_value(),
+                next.display_value(),
+            );
+        }
         ability.description = description;
     }
 }
@@ -231,44 +291,51 @@ pub fn update_level_descriptions_xp_radius(
     }
 }
 
-pub fn update_level_descriptions_xp_multiplier(
-    mut abilities: Query<(&mut AbilityLevel, &PassiveXPMultiplier), Changed<AbilityLevel>>,
+pub fn update_level_descriptions_crit_bonus(
+    mut abilities: Query<(&mut AbilityLevel, &PassiveCritBonus), Changed<AbilityLevel>>,
 ) {
-    for (mut ability, _flask) in abilities.iter_mut() {
-        info!("Updating xp mult description.");
-        let current_level = XPMultiplier::get_data_for_level(ability.level);
-        let next_level = XPMultiplier::get_data_for_level(ability.level + 1);
-        let mut description = "XP Multiplier".to_string();
+    for (mut ability, _) in abilities.iter_mut() {
+        let current_level = CritBonus::get_data_for_level(ability.level);
+        let next_level = CritBonus::get_data_for_level(ability.level + 1);
+        let mut description = "Precision".to_string();
+        push_stat_block(
+            &mut description,
+            "Crit Chance",
+            format!("{:.0}%", current_level.chance * 100.0),
+            format!("{:.0}%", next_level.chance * 100.0),
+        );
         push_stat_block(
             &mut description,
-            "Multiplier Bonus",
-            current_level.value,
-            next_level.value,
+            "Crit Damage",
+            format!("+{:.0}%", current_level.multiplier * 100.0),
+            format!("+{:.0}%", next_level.multiplier * 100.0),
         );
         ability.description = description;
     }
 }
 
 pub fn update_level_descriptions_flask(
-    mut abilities: Query<(&mut AbilityLevel, &Flask, &mut Cooldown), Changed<AbilityLevel>>,
+    mut abilities: Query<(&mut AbilityLevel, &Flask, &mut Cooldown, &GunId), Changed<AbilityLevel>>,
+    guns: Res<Guns>,
 ) {
-    for (mut ability, _flask, mut cooldown) in abilities.iter_mut() {
+    for (mut ability, _flask, mut cooldown, gun_id) in abilities.iter_mut() {
         info!("Updating flask description.");
         if (ability.level == 0) {
             ability.description =
                 "Molotov Cocktail \r\n Randomly summon an area of destruction.".to_string();
             return;
         }
-        let current_level = FlaskSpawnData::get_data_for_level(ability.level);
-        cooldown.timer = Timer::from_seconds(current_level.cooldown, TimerMode::Repeating);
-        let next_level = FlaskSpawnData::get_data_for_level(ability.level + 1);
+        let gun = guns.get(gun_id);
+        let current_level = FlaskSpawnData::from_gun_data(gun, ability.level);
+        cooldown.timer = Timer::from_seconds(gun.cooldown_for_level(ability.level), TimerMode::Repeating);
+        let next_level = FlaskSpawnData::from_gun_data(gun, ability.level + 1);
         let mut description = "Molotov Cocktail".to_string();
         // ability.description = format!("Molotov Cocktail\r\nSize:\r\n{} -> {}\r\n Cooldown:\r\n{} -> {}", current_level.scale, next_level.scale, current_level.cooldown.display_seconds(), next_level.cooldown.timer.display_seconds()).to_string();
         push_stat_block(
             &mut description,
             "Cooldown",
-            current_level.cooldown,
-            next_level.cooldown,
+            gun.cooldown_for_level(ability.level),
+            gun.cooldown_for_level(ability.level + 1),
         );
         push_stat_block(
             &mut description,
@@ -276,23 +343,166 @@ pub fn update_level_descriptions_flask(
             current_level.scale,
             next_level.scale,
         );
+        push_stat_block(
+            &mut description,
+            "Burn Damage",
+            current_level.burn.damage_per_tick,
+            next_level.burn.damage_per_tick,
+        );
+
+        ability.description = description;
+    }
+}
+
+pub fn update_level_descriptions_orbit(
+    mut abilities: Query<(&mut AbilityLevel, &OrbitGun, &mut Cooldown, &GunId), Changed<AbilityLevel>>,
+    guns: Res<Guns>,
+) {
+    for (mut ability, _, mut cooldown, gun_id) in abilities.iter_mut() {
+        info!("Updating orbit description.");
+        if (ability.level == 0) {
+            ability.description = "Orbit \r\n Projectiles circle around you, hitting anything they touch.".to_string();
+            return;
+        }
+        let gun = guns.get(gun_id);
+        let current_level = OrbitData::from_gun_data(gun, ability.level);
+        let next_level = OrbitData::from_gun_data(gun, ability.level + 1);
+        cooldown.timer = Timer::from_seconds(gun.cooldown_for_level(ability.level), TimerMode::Repeating);
+        let mut description = "Orbit".to_string();
+        push_stat_block(
+            &mut description,
+            "Projectiles",
+            current_level.count,
+            next_level.count,
+        );
+        push_stat_block(
+            &mut description,
+            "Damage",
+            current_level.damage,
+            next_level.damage,
+        );
+        push_stat_block(
+            &mut description,
+            "Radius",
+            current_level.radius,
+            next_level.radius,
+        );
+        push_stat_block(
+            &mut description,
+            "Speed",
+            current_level.angular_speed,
+            next_level.angular_speed,
+        );
+        push_stat_block(
+            &mut description,
+            "Duration",
+            current_level.uptime_seconds,
+            next_level.uptime_seconds,
+        );
+
+        ability.description = description;
+    }
+}
+
+pub fn update_level_descriptions_summon_munition(
+    mut abilities: Query<(&mut AbilityLevel, &mut Cooldown, &GunId), (With<SummonMunition>, Changed<AbilityLevel>)>,
+    guns: Res<Guns>,
+) {
+    for (mut ability, mut cooldown, gun_id) in abilities.iter_mut() {
+        if ability.level == 0 {
+            ability.description = "Summon Munition \r\n Reloads every weapon at once.".to_string();
+            continue;
+        }
+        let gun = guns.get(gun_id);
+        let current_level = SummonMunitionData::from_gun_data(gun, ability.level);
+        let next_level = SummonMunitionData::from_gun_data(gun, ability.level + 1);
+        cooldown.timer = Timer::from_seconds(current_level.cooldown_seconds, TimerMode::Repeating);
+        let mut description = "Summon Munition".to_string();
+        push_stat_block(
+            &mut description,
+            "Cooldown",
+            current_level.cooldown_seconds,
+            next_level.cooldown_seconds,
+        );
+        push_stat_block(
+            &mut description,
+            "Bonus Special Rounds",
+            current_level.overfill,
+            next_level.overfill,
+        );
+        ability.description = description;
+    }
+}
+
+pub fn update_level_descriptions_chain_lightning(
+    mut abilities: Query<(&mut AbilityLevel, &ChainLightningGun, &mut Cooldown, &GunId), Changed<AbilityLevel>>,
+    guns: Res<Guns>,
+) {
+    for (mut ability, _, mut cooldown, gun_id) in abilities.iter_mut() {
+        info!("Updating chain lightning description.");
+        if (ability.level == 0) {
+            ability.description = "Chain Lightning \r\n Strikes the nearest enemy, then jumps to the ones next to it.".to_string();
+            return;
+        }
+        let gun = guns.get(gun_id);
+        cooldown.timer = Timer::from_seconds(gun.cooldown_for_level(ability.level), TimerMode::Repeating);
+        let mut description = "Chain Lightning".to_string();
+        push_stat_block(
+            &mut description,
+            "Cooldown",
+            gun.cooldown_for_level(ability.level),
+            gun.cooldown_for_level(ability.level + 1),
+        );
+        push_stat_block(
+            &mut description,
+            "Damage",
+            gun.stat("damage", ability.level),
+            gun.stat("damage", ability.level + 1),
+        );
+        push_stat_block(
+            &mut description,
+            "Jumps",
+            gun.stat("jumps", ability.level) as u32,
+            gun.stat("jumps", ability.level + 1) as u32,
+        );
+        push_stat_block(
+            &mut description,
+            "Jump Radius",
+            gun.stat("jump_radius", ability.level),
+            gun.stat("jump_radius", ability.level + 1),
+        );
+        push_stat_block(
+            &mut description,
+            "Damage Kept Per Jump",
+            gun.stat("jump_falloff", ability.level),
+            gun.stat("jump_falloff", ability.level + 1),
+        );
 
         ability.description = description;
     }
 }
 
 pub fn update_level_descriptions_fireball(
-    mut abilities: Query<(&mut AbilityLevel, &FireBallGun), Changed<AbilityLevel>>,
+    mut abilities: Query<(&mut AbilityLevel, &FireBallGun, &mut Cooldown, &GunId), Changed<AbilityLevel>>,
+    guns: Res<Guns>,
 ) {
-    for (mut ability, _fireball) in abilities.iter_mut() {
+    for (mut ability, _fireball, mut cooldown, gun_id) in abilities.iter_mut() {
         info!("Updating fireball description.");
         if (ability.level == 0) {
             ability.description = "Fireball \r\n Throw a fireball that deals damage.".to_string();
             return;
         }
-        let current_level = FireballSpawnData::get_data_for_level(ability.level);
-        let next_level = FireballSpawnData::get_data_for_level(ability.level + 1);
+        let gun = guns.get(gun_id);
+        cooldown.timer = Timer::from_seconds(gun.cooldown_for_level(ability.level), TimerMode::Repeating);
+        let current_level = BulletSpawnData::from_gun_data(gun, ability.level);
+        let next_level = BulletSpawnData::from_gun_data(gun, ability.level + 1);
         let mut description = "Fireball".to_string();
+        push_stat_block(
+            &mut description,
+            "Cooldown",
+            gun.cooldown_for_level(ability.level),
+            gun.cooldown_for_level(ability.level + 1),
+        );
         // ability.description = format!("Molotov Cocktail\r\nSize:\r\n{} -> {}\r\n Cooldown:\r\n{} -> {}", current_level.scale, next_level.scale, current_level.cooldown.display_seconds(), next_level.cooldown.timer.display_seconds()).to_string();
         push_stat_block(
             &mut description,
@@ -318,23 +528,45 @@ pub fn update_level_descriptions_fireball(
             current_level.pierce,
             next_level.pierce,
         );
+        push_stat_block(
+            &mut description,
+            "Burn Damage",
+            gun.stat("burn_damage", ability.level),
+            gun.stat("burn_damage", ability.level + 1),
+        );
+        push_stat_block(
+            &mut description,
+            "Burn Duration",
+            gun.stat("burn_seconds", ability.level),
+            gun.stat("burn_seconds", ability.level + 1),
+        );
 
         ability.description = description;
     }
 }
 
 pub fn update_level_descriptions_pistol(
-    mut abilities: Query<(&mut AbilityLevel, &PistolGun), Changed<AbilityLevel>>,
+    mut abilities: Query<(&mut AbilityLevel, &PistolGun, &mut Cooldown, &GunId), Changed<AbilityLevel>>,
+    guns: Res<Guns>,
 ) {
-    for (mut ability, _fireball) in abilities.iter_mut() {
+    for (mut ability, _fireball, mut cooldown, gun_id) in abilities.iter_mut() {
         info!("Updating pistol description.");
         if (ability.level == 0) {
             ability.description = "Pistol \r\n It shoots bullets!".to_string();
             return;
         }
-        let current_level = PistolBulletSpawnData::get_data_for_level(ability.level);
-        let next_level = PistolBulletSpawnData::get_data_for_level(ability.level + 1);
+        let gun = guns.get(gun_id);
+        //the pistol resets its own cooldown when it shoots, so keep the timer mode.
+        cooldown.timer.set_duration(Duration::from_secs_f32(gun.cooldown_for_level(ability.level)));
+        let current_level = ProjectileSpawnData::from_gun_data(gun, ability.level);
+        let next_level = ProjectileSpawnData::from_gun_data(gun, ability.level + 1);
         let mut description = "Pistol".to_string();
+        push_stat_block(
+            &mut description,
+            "Cooldown",
+            gun.cooldown_for_level(ability.level),
+            gun.cooldown_for_level(ability.level + 1),
+        );
         // ability.description = format!("Molotov Cocktail\r\nSize:\r\n{} -> {}\r\n Cooldown:\r\n{} -> {}", current_level.scale, next_level.scale, current_level.cooldown.display_seconds(), next_level.cooldown.timer.display_seconds()).to_string();
         push_stat_block(
             &mut description,
@@ -365,31 +597,134 @@ pub fn update_level_descriptions_pistol(
     }
 }
 
+pub fn update_level_descriptions_shotgun(
+    mut abilities: Query<(&mut AbilityLevel, &ShotgunGun, &mut Cooldown, &GunId), Changed<AbilityLevel>>,
+    guns: Res<Guns>,
+) {
+    for (mut ability, _, mut cooldown, gun_id) in abilities.iter_mut() {
+        info!("Updating shotgun description.");
+        if (ability.level == 0) {
+            ability.description = "Shotgun \r\n Short range, wide blast.".to_string();
+            return;
+        }
+        let gun = guns.get(gun_id);
+        //the shotgun resets its own cooldown when it shoots, so keep the timer mode.
+        cooldown.timer.set_duration(Duration::from_secs_f32(gun.cooldown_for_level(ability.level)));
+        let current_level = ProjectileSpawnData::from_gun_data(gun, ability.level);
+        let next_level = ProjectileSpawnData::from_gun_data(gun, ability.level + 1);
+        let mut description = "Shotgun".to_string();
+        push_stat_block(
+            &mut description,
+            "Cooldown",
+            gun.cooldown_for_level(ability.level),
+            gun.cooldown_for_level(ability.level + 1),
+        );
+        push_stat_block(
+            &mut description,
+            "Pellets",
+            gun.stat("pellets", ability.level) as u32,
+            gun.stat("pellets", ability.level + 1) as u32,
+        );
+        push_stat_block(
+            &mut description,
+            "Spread",
+            gun.stat("spread_degrees", ability.level),
+            gun.stat("spread_degrees", ability.level + 1),
+        );
+        push_stat_block(
+            &mut description,
+            "Damage",
+            current_level.data.damage,
+            next_level.data.damage,
+        );
+        push_stat_block(
+            &mut description,
+            "Ricochets",
+            current_level.data.bounces,
+            next_level.data.bounces,
+        );
+
+        ability.description = description;
+    }
+}
+
+pub fn update_level_descriptions_sniper(
+    mut abilities: Query<(&mut AbilityLevel, &SniperGun, &mut Cooldown, &GunId), Changed<AbilityLevel>>,
+    guns: Res<Guns>,
+) {
+    for (mut ability, _, mut cooldown, gun_id) in abilities.iter_mut() {
+        info!("Updating sniper description.");
+        if (ability.level == 0) {
+            ability.description = "Sniper \r\n High damage, pierces every enemy in a line.".to_string();
+            return;
+        }
+        let gun = guns.get(gun_id);
+        //the sniper resets its own cooldown when it shoots, so keep the timer mode.
+        cooldown.timer.set_duration(Duration::from_secs_f32(gun.cooldown_for_level(ability.level)));
+        let mut description = "Sniper".to_string();
+        push_stat_block(
+            &mut description,
+            "Cooldown",
+            gun.cooldown_for_level(ability.level),
+            gun.cooldown_for_level(ability.level + 1),
+        );
+        push_stat_block(
+            &mut description,
+            "Damage",
+            gun.stat("damage", ability.level),
+            gun.stat("damage", ability.level + 1),
+        );
+        push_stat_block(
+            &mut description,
+            "Pierce",
+            gun.stat("pierce", ability.level) as u8,
+            gun.stat("pierce", ability.level + 1) as u8,
+        );
+        push_stat_block(
+            &mut description,
+            "Range",
+            gun.stat("range", ability.level),
+            gun.stat("range", ability.level + 1),
+        );
+
+        ability.description = description;
+    }
+}
+
 
 pub fn update_level_descriptions_iceball(
-    mut abilities: Query<(&mut AbilityLevel, &IceBallGun), Changed<AbilityLevel>>,
+    mut abilities: Query<(&mut AbilityLevel, &IceBallGun, &mut Cooldown, &GunId), Changed<AbilityLevel>>,
+    guns: Res<Guns>,
 ) {
-    for (mut ability, _) in abilities.iter_mut() {
+    for (mut ability, _, mut cooldown, gun_id) in abilities.iter_mut() {
         info!("Updating iceball description.");
         if (ability.level == 0) {
             ability.description = "Snowball \r\n Throw a snowball that slows enemies.\r\n After unloading a full cylinder, your first bullet applies freeze.".to_string();
             return;
         }
-        let current_level = IceballSpawnData::get_data_for_level(ability.level);
-        let next_level = IceballSpawnData::get_data_for_level(ability.level + 1);
+        let gun = guns.get(gun_id);
+        cooldown.timer = Timer::from_seconds(gun.cooldown_for_level(ability.level), TimerMode::Repeating);
+        let current_level = ProjectileSpawnData::from_gun_data(gun, ability.level);
+        let next_level = ProjectileSpawnData::from_gun_data(gun, ability.level + 1);
         let mut description = "Snowball".to_string();
+        push_stat_block(
+            &mut description,
+            "Cooldown",
+            gun.cooldown_for_level(ability.level),
+            gun.cooldown_for_level(ability.level + 1),
+        );
         // ability.description = format!("Molotov Cocktail\r\nSize:\r\n{} -> {}\r\n Cooldown:\r\n{} -> {}", current_level.scale, next_level.scale, current_level.cooldown.display_seconds(), next_level.cooldown.timer.display_seconds()).to_string();
         push_stat_block(
             &mut description,
             "Slow duration",
-            current_level.slow_seconds,
-            next_level.slow_seconds,
+            gun.stat("slow_seconds", ability.level),
+            gun.stat("slow_seconds", ability.level + 1),
         );
         push_stat_block(
             &mut description,
             "Bullet Lifetime",
-            current_level.bullet_lifetime_seconds,
-            next_level.bullet_lifetime_seconds,
+            current_level.lifetime_seconds,
+            next_level.lifetime_seconds,
         );
         push_stat_block(
             &mut description,
@@ -398,6 +733,13 @@ pub fn update_level_descriptions_iceball(
             next_level.data.damage,
         );
 
+        //0 turn rate means it doesn't home yet
+        push_stat_block(
+            &mut description,
+            "Homing",
+            current_level.homing.map_or(0.0, |homing| homing.turn_rate),
+            next_level.homing.map_or(0.0, |homing| homing.turn_rate),
+        );
         push_stat_block(
             &mut description,
             "Bullet Speed",
@@ -421,6 +763,43 @@ pub fn update_level_descriptions_iceball(
     }
 }
 
+pub fn update_level_descriptions_tome(
+    mut abilities: Query<(&mut AbilityLevel, &Tome), Changed<AbilityLevel>>,
+) {
+    for (mut ability, _) in abilities.iter_mut() {
+        let current_level = tome_modifiers(ability.level);
+        let next_level = tome_modifiers(ability.level + 1);
+        let mut description = "Tome".to_string();
+        for (current, next) in current_level.iter().zip(next_level.iter()) {
+            push_stat_block(
+                &mut description,
+                current.stat.label(),
+                current.display_value(),
+                next.display_value(),
+            );
+        }
+        ability.description = description;
+    }
+}
+
+//runs after the weapon's own description system, which still thinks it's the base weapon
+pub fn update_level_descriptions_evolved(
+    mut abilities: Query<(&mut AbilityLevel, &Evolved), Changed<AbilityLevel>>,
+    evolutions: Res<Evolutions>,
+) {
+    for (mut ability, evolved) in abilities.iter_mut() {
+        let recipe = evolutions.get(&evolved.recipe);
+        let stats = ability
+            .description
+            .split_once("\r\n")
+            .map(|(_, stats)| stats.to_string())
+            .unwrap_or_default();
+        //don't trip Changed<AbilityLevel> again
+        ability.bypass_change_detection().description =
+            format!("{} (evolved)\r\n{}\r\n{}", recipe.name, recipe.description, stats);
+    }
+}
+
 pub fn push_stat_block<T: PartialEq + Display>(
     desc: &mut String,
     label: impl Display,
@@ -439,7 +818,7 @@ pub fn push_stat_block<T: PartialEq + Display>(
 pub fn pick_up_xp_on_touch(
     mut commands: Commands,
     mut collision_events: EventReader<CollisionEvent>,
-    mut player_query: Query<(Entity, &Player, &mut XP, &XPMultiplier)>,
+    mut player_query: Query<(Entity, &Player, &mut XP, &PlayerStats)>,
     xp_query: Query<(Entity, &GainXPOnTouch)>,
 ) {
     for collision_event in collision_events.read() {
@@ -466,9 +845,9 @@ pub fn pick_up_xp_on_touch(
                     continue;
                 }
 
-                let (e_entity, _player, mut player_xp, xp_mult) = player.unwrap();
+                let (e_entity, _player, mut player_xp, stats) = player.unwrap();
                 info!("got xp!");
-                player_xp.amount += (xp.unwrap().1.value as f32) * (xp_mult.value + 1.0);
+                player_xp.amount += (xp.unwrap().1.value as f32) * stats.get(Stat::XPGain);
                 commands.entity(*xp_entity).despawn();
             }
             _ => {}
@@ -0,0 +1,257 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use crate::components::{ActiveReloadBuff, Burning, Cold, Dashing, Jammed, KnockedBack, StatusImmunities, StatusStacking, TemporaryComponent};
+use crate::systems::guns::Damaged;
+use crate::AppState;
+
+//adding a new status effect
+//1.. add a component that derives TemporaryComponent
+//2.. implement StatusEffect for it
+//3.. register it in StatusEffectPlugin
+//4.. apply it by sending an ApplyStatusEvent
+pub trait StatusEffect: TemporaryComponent + Component + Clone {
+    /// Used to look up immunities.
+    const NAME: &'static str;
+    /// Used when the source of the status doesn't pick its own stacking.
+    const STACKING: StatusStacking;
+    /// Stacking can never push the magnitude above this.
+    const MAX_MAGNITUDE: f32;
+
+    fn magnitude(&self) -> f32 {
+        1.0
+    }
+
+    fn set_magnitude(&mut self, _magnitude: f32) {}
+}
+
+impl StatusEffect for Cold {
+    const NAME: &'static str = "cold";
+    const STACKING: StatusStacking = StatusStacking::Strongest;
+    const MAX_MAGNITUDE: f32 = 1.0;
+
+    fn magnitude(&self) -> f32 {
+        self.multiplier
+    }
+
+    fn set_magnitude(&mut self, magnitude: f32) {
+        self.multiplier = magnitude;
+    }
+}
+
+impl StatusEffect for Burning {
+    const NAME: &'static str = "burning";
+    const STACKING: StatusStacking = StatusStacking::Refresh;
+    const MAX_MAGNITUDE: f32 = 10.0;
+
+    fn magnitude(&self) -> f32 {
+        self.damage_per_tick
+    }
+
+    fn set_magnitude(&mut self, magnitude: f32) {
+        self.damage_per_tick = magnitude;
+    }
+}
+
+impl StatusEffect for Dashing {
+    const NAME: &'static str = "dashing";
+    const STACKING: StatusStacking = StatusStacking::Refresh;
+    const MAX_MAGNITUDE: f32 = 1.0;
+}
+
+pub struct StatusEffectPlugin;
+
+impl Plugin for StatusEffectPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_temporary_component::<Damaged>()
+            .register_temporary_component::<Jammed>()
+            .register_temporary_component::<ActiveReloadBuff>()
+            .register_temporary_component::<KnockedBack>()
+            .register_status_effect::<Cold>()
+            .register_status_effect::<Burning>()
+            .register_status_effect::<Dashing>();
+    }
+}
+
+pub trait StatusEffectAppExt {
+    /// Ticks `T` and removes it when its timer finishes.
+    fn register_temporary_component<T: TemporaryComponent + Component>(&mut self) -> &mut Self;
+    /// Like `register_temporary_component`, but `T` is applied through [`ApplyStatusEvent`]
+    /// so stacking and immunities are respected.
+    fn register_status_effect<T: StatusEffect>(&mut self) -> &mut Self;
+}
+
+impl StatusEffectAppExt for App {
+    fn register_temporary_component<T: TemporaryComponent + Component>(&mut self) -> &mut Self {
+        self.add_systems(
+            Update,
+            process_temporary_component::<T>.run_if(in_state(AppState::InGame)),
+        )
+    }
+
+    fn register_status_effect<T: StatusEffect>(&mut self) -> &mut Self {
+        self.add_event::<ApplyStatusEvent<T>>().add_systems(
+            Update,
+            (apply_status_effects::<T>, process_temporary_component::<T>)
+                .chain()
+                .run_if(in_state(AppState::InGame)),
+        )
+    }
+}
+
+#[derive(Event)]
+pub struct ApplyStatusEvent<T: StatusEffect> {
+    pub target: Entity,
+    pub effect: T,
+    pub stacking: StatusStacking,
+}
+
+impl<T: StatusEffect> ApplyStatusEvent<T> {
+    pub fn new(target: Entity, effect: T) -> Self {
+        Self {
+            target,
+            effect,
+            stacking: T::STACKING,
+        }
+    }
+
+    pub fn with_stacking(mut self, stacking: StatusStacking) -> Self {
+        self.stacking = stacking;
+        self
+    }
+}
+
+pub fn apply_status_effects<T: StatusEffect>(
+    mut events: EventReader<ApplyStatusEvent<T>>,
+    mut targets: Query<(Option<&mut T>, Option<&StatusImmunities>)>,
+    mut commands: Commands,
+) {
+    //statuses applied to an entity that doesn't have one yet this frame are merged here,
+    //otherwise the last insert would win.
+    let mut new_statuses: HashMap<Entity, T> = HashMap::new();
+    for event in events.read() {
+        let Ok((current, immunities)) = targets.get_mut(event.target) else {
+            continue;
+        };
+        if immunities.is_some_and(|immunities| immunities.is_immune_to(T::NAME)) {
+            continue;
+        }
+        let mut incoming = event.effect.clone();
+        incoming.set_magnitude(incoming.magnitude().min(T::MAX_MAGNITUDE));
+
+        if let Some(mut current) = current {
+            merge_status(&mut *current, &incoming, event.stacking);
+        } else if let Some(pending) = new_statuses.get_mut(&event.target) {
+            merge_status(pending, &incoming, event.stacking);
+        } else {
+            new_statuses.insert(event.target, incoming);
+        }
+    }
+
+    for (entity, status) in new_statuses {
+        commands.entity(entity).insert(status);
+    }
+}
+
+/// Only touches magnitude and duration, so effects can keep their own state (eg. tick timers).
+fn merge_status<T: StatusEffect>(current: &mut T, incoming: &T, stacking: StatusStacking) {
+    match stacking {
+        StatusStacking::Refresh => {
+            current.set_magnitude(incoming.magnitude());
+            *current.timer_mut() = incoming.timer().clone();
+        }
+        StatusStacking::Extend => {
+            let remaining = current.timer().remaining();
+            current.set_magnitude(current.magnitude().max(incoming.magnitude()));
+            *current.timer_mut() =
+                Timer::new(remaining + incoming.timer().duration(), TimerMode::Once);
+        }
+        StatusStacking::Stack { max_stacks } => {
+            let max = incoming.magnitude() * max_stacks as f32;
+            current.set_magnitude((current.magnitude() + incoming.magnitude()).min(max));
+            *current.timer_mut() = incoming.timer().clone();
+        }
+        StatusStacking::Strongest => {
+            if incoming.magnitude() >= current.magnitude() {
+                current.set_magnitude(incoming.magnitude());
+                *current.timer_mut() = incoming.timer().clone();
+            }
+        }
+    }
+    current.set_magnitude(current.magnitude().min(T::MAX_MAGNITUDE));
+}
+
+pub fn process_temporary_component<T>(
+    mut damaged: Query<(Entity, &mut T)>,
+    time: Res<Time>,
+    mut commands: Commands,
+) where
+    T: TemporaryComponent + Component,
+{
+    for (entity, mut damaged) in damaged.iter_mut() {
+        damaged.advance_timer(time.delta());
+        if (damaged.is_finished()) {
+            commands.entity(entity).remove::<T>();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn merge_status_follows_each_stacking_policy() {
+        let burning = Burning {
+            damage_per_tick: 2.0,
+            tick_timer: Timer::from_seconds(0.5, TimerMode::Repeating),
+            timer: Timer::from_seconds(3.0, TimerMode::Once),
+        };
+        let weaker = Burning {
+            damage_per_tick: 1.0,
+            timer: Timer::from_seconds(4.0, TimerMode::Once),
+            ..burning.clone()
+        };
+
+        //refresh takes the new strength and restarts the duration
+        let mut current = burning.clone();
+        current.timer.tick(Duration::from_secs(1));
+        merge_status(&mut current, &weaker, StatusStacking::Refresh);
+        assert_eq!(current.damage_per_tick, 1.0);
+        assert_eq!(current.timer.remaining(), Duration::from_secs(4));
+
+        //extend adds the new duration to what is left and keeps the stronger
+        let mut current = burning.clone();
+        current.timer.tick(Duration::from_secs(1));
+        merge_status(&mut current, &weaker, StatusStacking::Extend);
+        assert_eq!(current.damage_per_tick, 2.0);
+        assert_eq!(current.timer.remaining(), Duration::from_secs(6));
+
+        //stack adds up, but only `max_stacks` applications worth
+        let mut current = weaker.clone();
+        for _ in 0..4 {
+            merge_status(&mut current, &weaker, StatusStacking::Stack { max_stacks: 3 });
+        }
+        assert_eq!(current.damage_per_tick, 3.0);
+
+        //strongest ignores anything weaker
+        let mut current = burning.clone();
+        merge_status(&mut current, &weaker, StatusStacking::Strongest);
+        assert_eq!(current.damage_per_tick, 2.0);
+        assert_eq!(current.timer.duration(), Duration::from_secs(3));
+    }
+
+    #[test]
+    fn stacking_never_goes_past_max_magnitude() {
+        let mut current = Burning {
+            damage_per_tick: 6.0,
+            ..default()
+        };
+        let incoming = current.clone();
+        merge_status(&mut current, &incoming, StatusStacking::Stack { max_stacks: 100 });
+        assert_eq!(current.damage_per_tick, Burning::MAX_MAGNITUDE);
+    }
+}
@@ -0,0 +1,152 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::components::Player;
+use crate::systems::implants::Implant;
+use crate::systems::loadout::AbilityKind;
+use crate::systems::modifiers::{AbilityStats, PlayerStats, StatModifier, StatModifiers};
+
+//synergies
+//1.. abilities and implants carry Tags
+//2.. a synergy rule grants abilities with the target tag a modifier for every owned source with the source tag
+//3.. resolve_ability_stats folds the player's modifiers plus the matching synergies into each ability's AbilityStats
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Tag {
+    Magic,
+    Metal,
+    Fire,
+    Ice,
+}
+
+impl Tag {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Tag::Magic => "Magic",
+            Tag::Metal => "Metal",
+            Tag::Fire => "Fire",
+            Tag::Ice => "Ice",
+        }
+    }
+}
+
+#[derive(Component, Clone, Debug, Default)]
+pub struct Tags {
+    pub tags: Vec<Tag>,
+}
+
+impl Tags {
+    pub fn has(&self, tag: Tag) -> bool {
+        self.tags.contains(&tag)
+    }
+}
+
+/// What counts as a source for a synergy rule.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SynergySource {
+    Implants,
+    Abilities,
+    #[default]
+    Any,
+}
+
+impl SynergySource {
+    fn matches(&self, is_implant: bool, is_ability: bool) -> bool {
+        match self {
+            SynergySource::Implants => is_implant,
+            SynergySource::Abilities => is_ability,
+            SynergySource::Any => is_implant || is_ability,
+        }
+    }
+}
+
+/// Eg. "+20% might to all magic abilities per metal implant".
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SynergyRule {
+    pub name: String,
+    pub description: String,
+    pub target: Tag,
+    pub source: Tag,
+    #[serde(default)]
+    pub from: SynergySource,
+    //stacked once per source, see StatModifier::for_level
+    pub per_source: StatModifier,
+}
+
+#[derive(Resource)]
+pub struct Synergies {
+    //keyed by file name, eg. prefabs/synergies/conductive_alloys.yaml -> conductive_alloys
+    pub rules: HashMap<String, SynergyRule>,
+}
+
+/// A synergy that currently does something, for the loadout panel.
+#[derive(Clone, Debug)]
+pub struct ActiveSynergy {
+    pub name: String,
+    pub description: String,
+    //ability name and what it gets from this synergy
+    pub targets: Vec<(String, StatModifier)>,
+}
+
+#[derive(Resource, Default)]
+pub struct ActiveSynergies {
+    pub synergies: Vec<ActiveSynergy>,
+}
+
+pub fn resolve_ability_stats(
+    synergies: Res<Synergies>,
+    mut active: ResMut<ActiveSynergies>,
+    modifiers: Query<&StatModifiers>,
+    changed_stats: Query<(), (With<Player>, Changed<PlayerStats>)>,
+    changed_tags: Query<(), Changed<Tags>>,
+    mut removed: RemovedComponents<Tags>,
+    sources: Query<(Entity, &Tags, Has<Implant>, Has<AbilityKind>)>,
+    mut abilities: Query<(Entity, &Name, &Tags, &mut AbilityStats)>,
+) {
+    let removed_any = removed.read().count() > 0;
+    if changed_stats.is_empty() && changed_tags.is_empty() && !removed_any {
+        return;
+    }
+    let player_modifiers: Vec<StatModifier> = modifiers
+        .iter()
+        .flat_map(|modifiers| modifiers.modifiers.iter().copied())
+        .collect();
+    //sorted so the panel doesn't shuffle around every time this runs
+    let mut rules: Vec<(&String, &SynergyRule)> = synergies.rules.iter().collect();
+    rules.sort_by_key(|(id, _)| *id);
+
+    let mut active_synergies: Vec<ActiveSynergy> = vec![];
+    for (ability, name, tags, mut stats) in abilities.iter_mut() {
+        let mut ability_modifiers = player_modifiers.clone();
+        for (_, rule) in rules.iter().filter(|(_, rule)| tags.has(rule.target)) {
+            //an ability doesn't feed its own synergies
+            let count = sources
+                .iter()
+                .filter(|(source, source_tags, is_implant, is_ability)| {
+                    *source != ability && source_tags.has(rule.source) && rule.from.matches(*is_implant, *is_ability)
+                })
+                .count();
+            if count == 0 {
+                continue;
+            }
+            let modifier = rule.per_source.for_level(count as u8);
+            ability_modifiers.push(modifier);
+
+            let target = (name.to_string(), modifier);
+            match active_synergies.iter_mut().find(|active| active.name == rule.name) {
+                Some(active) => active.targets.push(target),
+                None => active_synergies.push(ActiveSynergy {
+                    name: rule.name.clone(),
+                    description: rule.description.clone(),
+                    targets: vec![target],
+                }),
+            }
+        }
+        stats.stats = PlayerStats::resolve(ability_modifiers.iter());
+    }
+    active.synergies = active_synergies;
+}
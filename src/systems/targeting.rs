@@ -0,0 +1,98 @@
+use bevy::ecs::system::SystemParam;
+use bevy::math::Vec3Swizzles;
+use bevy::prelude::{Commands, Entity, GlobalTransform, Query, Res, Vec2, With, Without};
+use bevy_prng::WyRand;
+use bevy_rand::prelude::GlobalEntropy;
+use bevy_rapier2d::geometry::{Collider, CollisionGroups};
+use bevy_rapier2d::plugin::RapierContext;
+use bevy_rapier2d::prelude::QueryFilter;
+use rand_core::RngCore;
+
+use crate::components::{Enemy, Health, Targeting, TargetingStrategy};
+use crate::initialization::load_prefabs::{GunId, Guns};
+use crate::physics::layers::game_layer;
+use crate::systems::input::AimDirection;
+
+//enemies this close to each other count towards the same cluster
+const CLUSTER_RADIUS: f32 = 100.0;
+
+/// Guns start out with the targeting from their prefab.
+pub fn init_targeting_from_gun_data(
+    query: Query<(Entity, &GunId), Without<Targeting>>,
+    guns: Res<Guns>,
+    mut commands: Commands,
+) {
+    for (entity, gun_id) in query.iter() {
+        if let Some(targeting) = guns.get(gun_id).targeting {
+            commands.entity(entity).insert(targeting);
+        }
+    }
+}
+
+#[derive(SystemParam)]
+pub struct TargetFinder<'w, 's> {
+    enemies: Query<'w, 's, (&'static GlobalTransform, &'static Health), With<Enemy>>,
+    rapier_context: Res<'w, RapierContext>,
+    aim_direction: Res<'w, AimDirection>,
+}
+
+impl<'w, 's> TargetFinder<'w, 's> {
+    /// Where a weapon at `origin` should shoot, or `None` if nothing valid is in range.
+    pub fn find(
+        &self,
+        targeting: &Targeting,
+        origin: Vec2,
+        rng: &mut GlobalEntropy<WyRand>,
+    ) -> Option<Vec2> {
+        let mut in_range = self
+            .enemies
+            .iter()
+            .map(|(transform, health)| (transform.translation().xy(), health.value))
+            .filter(|(position, _)| position.distance(origin) <= targeting.range);
+
+        match targeting.strategy {
+            TargetingStrategy::ManualAim => {
+                //aimed by hand, but still holds fire while there's nothing in range to hit
+                if self.aim_direction.0 == Vec2::ZERO || in_range.next().is_none() {
+                    return None;
+                }
+                Some(origin + self.aim_direction.0 * targeting.range)
+            }
+            TargetingStrategy::Nearest => in_range
+                .min_by(|a, b| a.0.distance_squared(origin).total_cmp(&b.0.distance_squared(origin)))
+                .map(|(position, _)| position),
+            TargetingStrategy::FurthestInRange => in_range
+                .max_by(|a, b| a.0.distance_squared(origin).total_cmp(&b.0.distance_squared(origin)))
+                .map(|(position, _)| position),
+            TargetingStrategy::HighestHealth => in_range
+                .max_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(position, _)| position),
+            TargetingStrategy::RandomInRange => {
+                let candidates: Vec<Vec2> = in_range.map(|(position, _)| position).collect();
+                if candidates.is_empty() {
+                    return None;
+                }
+                Some(candidates[rng.next_u32() as usize % candidates.len()])
+            }
+            TargetingStrategy::DensestCluster => in_range
+                .map(|(position, _)| (position, self.count_enemies_near(position)))
+                .max_by_key(|(_, count)| *count)
+                .map(|(position, _)| position),
+        }
+    }
+
+    fn count_enemies_near(&self, position: Vec2) -> usize {
+        let mut count = 0;
+        self.rapier_context.intersections_with_shape(
+            position,
+            0.0,
+            &Collider::ball(CLUSTER_RADIUS),
+            QueryFilter::new().groups(CollisionGroups::new(game_layer::PLAYER, game_layer::ENEMY)),
+            |_| {
+                count += 1;
+                true
+            },
+        );
+        count
+    }
+}
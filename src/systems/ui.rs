@@ -5,7 +5,14 @@ use bevy_egui::{egui, EguiContexts};
 use egui::{Color32, SizeHint, TextureFilter};
 use rand::seq::IteratorRandom;
 
-use crate::components::{AbilityLevel, Ammo, ApplyColdOnTouch, Chambered, Cooldown, Health, HealthUi, Lifetime, Player, XP};
+use crate::components::{AbilityLevel, Ammo, Chambered, Cooldown, Health, HealthUi, Jammed, Lifetime, Mana, Player, Reloading, SpecialAmmoQueue, SpecialRound, Targeting, TargetingStrategy, XP};
+use crate::constants::{IMPLANT_SLOTS, MAX_ABILITY_LEVEL};
+use crate::initialization::load_prefabs::{ActiveReloadConfig, GunId, Guns};
+use crate::systems::evolution::{EvolveEvent, Evolutions};
+use crate::systems::implants::{Implant, ImplantData, Implants, InstallImplantEvent};
+use crate::systems::loadout::{available_to_learn, AbilityCategory, AbilityKind, LearnAbilityEvent};
+use crate::systems::modifiers::PlayerStats;
+use crate::systems::synergies::ActiveSynergies;
 use crate::AppState;
 use serde::{Deserialize, Serialize};
 
@@ -52,10 +59,25 @@ fn button_text(_asset_server: &Res<AssetServer>, text: &str) -> TextBundle {
     }
 }
 
+/// One bullet in the ammo panel, the image is baked into the binary.
+fn bullet_image(height: f32, tint: egui::Color32) -> egui::Image<'static> {
+    //relative to this file
+    egui::Image::new(egui::include_image!("../../assets/sprites/ui-bullet.png"))
+        .texture_options(TextureOptions {
+            magnification: TextureFilter::Nearest,
+            minification: TextureFilter::Nearest,
+            wrap_mode: Default::default(),
+        })
+        .max_height(height)
+        .tint(tint)
+}
+
 pub fn show_bullets(
-    ammo_query: Query<(&Ammo, Option<&Children>)>,
-    bullet_query: Query<(&Chambered, Option<&ApplyColdOnTouch>)>,
+    ammo_query: Query<(&Ammo, &Name, &AbilityLevel, Option<&Children>, Option<&Reloading>, Option<&GunId>, Has<Jammed>)>,
+    bullet_query: Query<(&Chambered, Option<&SpecialRound>)>,
     cooldown_query: Query<(&Cooldown, &Name, &AbilityLevel)>,
+    special_ammo: Query<&SpecialAmmoQueue>,
+    guns: Res<Guns>,
     mut contexts: EguiContexts,
 ) {
     let panel = egui::panel::SidePanel::left("ammo panel").frame(egui::Frame {
@@ -74,41 +96,184 @@ pub fn show_bullets(
             ui.label(format!("{0} : {1:.2}/{2:.2}", name, cd.timer.elapsed().as_secs_f32(), cd.display_seconds()));
             ui.add(egui::widgets::ProgressBar::new(fraction).show_percentage());
         }
-        let label_height = 8;
+        let label_height = ui.text_style_height(&egui::TextStyle::Body);
 
-
-        let (clip, maybe_bullets) = ammo_query.single();
         // Add a flexible space to push the next elements to the bottom
         let bullet_height = 20.0;
+        let spacing = ui.style().spacing.item_spacing.y;
+        let mut total_height = 0.0;
+        for (_, _, level, maybe_bullets, reloading, _, jammed) in ammo_query.iter() {
+            if level.level == 0 {
+                continue;
+            }
+            let num_bullets = maybe_bullets.map_or(0, |bullets| bullets.len());
+            total_height += label_height + spacing + num_bullets as f32 * (bullet_height + spacing);
+            if reloading.is_some() || jammed {
+                total_height += label_height + spacing;
+            }
+        }
+        let queued_rounds = special_ammo.get_single().map_or(0, |queue| queue.rounds.len());
+        if queued_rounds > 0 {
+            total_height += label_height + spacing + bullet_height + spacing;
+        }
+        ui.add_space(ui.available_size().y - total_height);
 
-        if let Some(bullets) = maybe_bullets
-        {
-            let num_bullets: usize = bullets.len();
-            ui.add_space(ui.available_size().y - (num_bullets as f32 * (bullet_height + ui.style().spacing.item_spacing.y)));
+        for (_, name, level, maybe_bullets, reloading, gun_id, jammed) in ammo_query.iter() {
+            if level.level == 0 {
+                continue;
+            }
+            ui.label(name.to_string());
+            if jammed {
+                ui.colored_label(Color32::RED, "JAMMED");
+            } else if let Some(reloading) = reloading {
+                let active_reload = gun_id.and_then(|gun_id| guns.get(gun_id).active_reload.as_ref());
+                match active_reload {
+                    Some(config) => active_reload_bar(ui, reloading.total.fraction(), config, label_height),
+                    None => {
+                        ui.add(egui::widgets::ProgressBar::new(reloading.total.fraction()));
+                    }
+                }
+            }
+            let Some(bullets) = maybe_bullets else { continue; };
             for bullet in bullets.iter() {
-                let Ok((_, cold)) = bullet_query.get(*bullet) else { todo!() };
-                let handle = egui::include_image!("E:/Unity Projects/rust-survivors/assets/sprites/ui-bullet.png");
+                let Ok((_, round)) = bullet_query.get(*bullet) else { continue; };
+                let tint = round.map_or(egui::Color32::from_rgb(255, 255, 255), special_round_tint);
+                ui.add(bullet_image(bullet_height, tint));
+            }
+        }
 
-                let mut tint =egui::Color32::from_rgb(255, 255, 255);
+        let Ok(queue) = special_ammo.get_single() else { return; };
+        if queue.rounds.is_empty() {
+            return;
+        }
+        ui.label("Special Rounds");
+        ui.horizontal(|ui| {
+            for round in queue.rounds.iter() {
+                ui.add(bullet_image(bullet_height, special_round_tint(round)));
+            }
+        });
+    });
+}
 
-                if cold.is_some() {
-                    tint = egui::Color32::from_rgb(0, 0, 255);
+pub fn show_mana(player: Query<&Mana, With<Player>>, mut contexts: EguiContexts) {
+    let Ok(mana) = player.get_single() else {
+        return;
+    };
+    let panel = egui::panel::TopBottomPanel::bottom("mana panel").frame(egui::Frame {
+        fill: egui::Color32::from_rgba_unmultiplied(0, 0, 0, 0),
+        ..Default::default()
+    });
+    panel.show(contexts.ctx_mut(), |ui| {
+        let fraction = if mana.max > 0.0 { mana.value / mana.max } else { 0.0 };
+        ui.add(
+            egui::widgets::ProgressBar::new(fraction)
+                .fill(Color32::from_rgb(40, 90, 220))
+                .text(format!("Mana {:.0}/{:.0}", mana.value, mana.max)),
+        );
+    });
+}
+
+/// Lets the player swap how each weapon picks its targets.
+pub fn targeting_window(
+    mut weapons: Query<(&Name, &AbilityLevel, &mut Targeting)>,
+    mut contexts: EguiContexts,
+) {
+    egui::Window::new("Targeting")
+        .default_open(false)
+        .show(contexts.ctx_mut(), |ui| {
+            for (name, level, mut targeting) in weapons.iter_mut() {
+                if level.level == 0 {
+                    continue;
+                }
+                let mut strategy = targeting.strategy;
+                egui::ComboBox::from_label(name.to_string())
+                    .selected_text(strategy.label())
+                    .show_ui(ui, |ui| {
+                        for option in TargetingStrategy::ALL {
+                            ui.selectable_value(&mut strategy, option, option.label());
+                        }
+                    });
+                //only touch the component when it actually changes
+                if strategy != targeting.strategy {
+                    targeting.strategy = strategy;
                 }
+            }
+        });
+}
 
-                ui.add(
-                    egui::Image::new(handle)
-                        .texture_options(TextureOptions {
-                            magnification: TextureFilter::Nearest,
-                            minification: TextureFilter::Nearest,
-                            wrap_mode: Default::default(),
-                        })
-                        .max_height(bullet_height)
-                        .tint(tint)
-                );
+/// Learned abilities per slot category, installed implants and the synergies between them.
+pub fn loadout_panel(
+    abilities: Query<(&AbilityKind, &Name, &AbilityLevel)>,
+    installed_implants: Query<&Name, With<Implant>>,
+    player: Query<&PlayerStats, With<Player>>,
+    synergies: Res<ActiveSynergies>,
+    mut contexts: EguiContexts,
+) {
+    let Ok(stats) = player.get_single() else {
+        return;
+    };
+    egui::Window::new("Loadout")
+        .default_open(false)
+        .show(contexts.ctx_mut(), |ui| {
+            for category in [AbilityCategory::Weapon, AbilityCategory::Spell, AbilityCategory::Passive] {
+                let learned: Vec<(&Name, &AbilityLevel)> = abilities
+                    .iter()
+                    .filter(|(kind, _, _)| kind.category() == category)
+                    .map(|(_, name, level)| (name, level))
+                    .collect();
+                ui.strong(format!(
+                    "{}s ({}/{})",
+                    category.label(),
+                    learned.len(),
+                    stats.slots(category.slot_stat())
+                ));
+                for (name, level) in learned {
+                    ui.label(format!("  {} lvl {}", name, level.level));
+                }
             }
-        }
 
-    });
+            let implants: Vec<&Name> = installed_implants.iter().collect();
+            ui.strong(format!("Implants ({}/{})", implants.len(), IMPLANT_SLOTS));
+            for name in implants {
+                ui.label(format!("  {}", name));
+            }
+
+            ui.separator();
+            ui.strong("Synergies");
+            if synergies.synergies.is_empty() {
+                ui.colored_label(Color32::GRAY, "  none active");
+            }
+            for synergy in synergies.synergies.iter() {
+                ui.colored_label(Color32::LIGHT_BLUE, format!("  {}", synergy.name));
+                ui.colored_label(Color32::GRAY, format!("  {}", synergy.description));
+                for (ability, modifier) in synergy.targets.iter() {
+                    ui.colored_label(Color32::GREEN, format!("    {}: {}", ability, modifier));
+                }
+            }
+        });
+}
+
+/// Reload progress with the active reload window drawn on top of it.
+fn active_reload_bar(ui: &mut egui::Ui, progress: f32, config: &ActiveReloadConfig, height: f32) {
+    let width = ui.available_width().min(150.0);
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(width, height), egui::Sense::hover());
+    let painter = ui.painter();
+    painter.rect_filled(rect, 2.0, Color32::from_gray(40));
+    let window = egui::Rect::from_x_y_ranges(
+        rect.left() + rect.width() * config.window_start..=rect.left() + rect.width() * config.window_end,
+        rect.y_range(),
+    );
+    painter.rect_filled(window, 0.0, Color32::from_rgb(60, 180, 60));
+    let x = rect.left() + rect.width() * progress;
+    painter.line_segment(
+        [egui::pos2(x, rect.top()), egui::pos2(x, rect.bottom())],
+        egui::Stroke::new(2.0, Color32::WHITE),
+    );
+}
+
+fn special_round_tint(round: &SpecialRound) -> Color32 {
+    let [r, g, b, a] = round.color().as_rgba_u8();
+    Color32::from_rgba_unmultiplied(r, g, b, a)
 }
 
 pub fn button_system(
@@ -116,6 +281,15 @@ pub fn button_system(
     mut next_state: ResMut<NextState<AppState>>,
     commands: Commands,
     choices: Query<&LevelUpChoice>,
+    evolution_choices: Query<&EvolutionChoice>,
+    evolutions: Res<Evolutions>,
+    mut evolve_events: EventWriter<EvolveEvent>,
+    implant_choices: Query<&ImplantChoice>,
+    implants: Res<Implants>,
+    installed_implants: Query<(), With<Implant>>,
+    mut install_events: EventWriter<InstallImplantEvent>,
+    learn_choices: Query<&LearnAbilityChoice>,
+    mut learn_events: EventWriter<LearnAbilityEvent>,
     mut abilities: Query<&mut AbilityLevel>,
     mut contexts: EguiContexts,
 ) {
@@ -132,6 +306,59 @@ pub fn button_system(
 
             ui.vertical_centered(|ui| {
                 ui.add_space(100.0);
+                //evolutions go first so they don't get missed
+                for choice in evolution_choices.iter() {
+                    let recipe = evolutions.get(&choice.recipe);
+                    let text = format!("EVOLVE: {} + {} -> {}\r\n{}", recipe.weapon, recipe.passive, recipe.name, recipe.description);
+                    if ui.add(egui::Button::new(egui::RichText::new(text).color(Color32::GOLD))
+                        .fill(Color32::from_rgb(60, 40, 0))
+                        .min_size(emath::Vec2::new(button_width, button_height)))
+                        .clicked() {
+                        info!("Evolution {} clicked", recipe.name);
+                        evolve_events.send(EvolveEvent {
+                            base: choice.base,
+                            recipe: choice.recipe.clone(),
+                        });
+                        next_state.set(AppState::InGame);
+                        return;
+                    }
+                }
+                let installed = installed_implants.iter().count();
+                for choice in implant_choices.iter() {
+                    let data = implants.get(&choice.implant);
+                    if ui.add(egui::Button::new(implant_button_text(data, installed))
+                        .fill(Color32::from_rgb(20, 40, 50))
+                        .min_size(emath::Vec2::new(button_width, button_height)))
+                        .clicked() {
+                        info!("Implant {} clicked", data.name);
+                        install_events.send(InstallImplantEvent {
+                            implant: choice.implant.clone(),
+                        });
+                        next_state.set(AppState::InGame);
+                        return;
+                    }
+                }
+                for choice in learn_choices.iter() {
+                    let category = choice.ability.category();
+                    let text = format!(
+                        "NEW {}: {} (slot {}/{})\r\n{}",
+                        category.label(),
+                        choice.ability.name(),
+                        choice.used_slots + 1,
+                        choice.slots,
+                        choice.ability.blurb()
+                    );
+                    if ui.add(egui::Button::new(text)
+                        .min_size(emath::Vec2::new(button_width, button_height)))
+                        .clicked() {
+                        info!("New ability {} clicked", choice.ability.name());
+                        learn_events.send(LearnAbilityEvent {
+                            ability: choice.ability,
+                        });
+                        next_state.set(AppState::InGame);
+                        return;
+                    }
+                }
                 for choice in choices.iter() {
                     let mut ability = abilities.get_mut(choice.entity_to_level).unwrap();
                     if ui.add(egui::Button::new(ability.description.to_string())//.fill(egui::Color32::from_rgba_premultiplied(0, 0, 0, 255))
@@ -150,30 +377,133 @@ pub fn button_system(
         });
 }
 
+//upsides in green and downsides in red, so the catch can't be missed
+fn implant_button_text(data: &ImplantData, installed: usize) -> egui::text::LayoutJob {
+    let mut lines = vec![
+        (format!("IMPLANT: {} (slot {}/{})", data.name, installed + 1, IMPLANT_SLOTS), Color32::WHITE),
+        (data.description.clone(), Color32::LIGHT_GRAY),
+    ];
+    for upside in data.upsides.iter() {
+        lines.push((format!("Upside: {}", upside), Color32::GREEN));
+    }
+    for downside in data.downsides.iter() {
+        lines.push((format!("Downside: {}", downside), Color32::RED));
+    }
+
+    let mut job = egui::text::LayoutJob::default();
+    for (index, (text, color)) in lines.into_iter().enumerate() {
+        let text = if index == 0 { text } else { format!("\r\n{}", text) };
+        job.append(&text, 0.0, egui::TextFormat {
+            color,
+            ..default()
+        });
+    }
+    job
+}
+
 #[derive(Component, Debug, Serialize, Deserialize)]
 pub struct LevelUpChoice {
     // pub description: String,
     pub entity_to_level: Entity,
 }
 
-pub fn prepare_level_up(abilities: Query<(Entity, &AbilityLevel, &Name)>, mut commands: Commands) {
+/// Offered on top of the regular choices when a max level weapon has the passive its recipe needs.
+#[derive(Component, Debug)]
+pub struct EvolutionChoice {
+    pub base: Entity,
+    pub recipe: String,
+}
+
+#[derive(Component, Debug)]
+pub struct ImplantChoice {
+    pub implant: String,
+}
+
+/// An ability the player doesn't have yet, with how full its category is.
+#[derive(Component, Debug)]
+pub struct LearnAbilityChoice {
+    pub ability: AbilityKind,
+    pub used_slots: usize,
+    pub slots: usize,
+}
+
+//anything the regular choices can be picked from
+enum LevelUpPoolEntry {
+    Ability(Entity),
+    Learn(AbilityKind),
+    Implant(String),
+}
+
+pub fn prepare_level_up(
+    abilities: Query<(Entity, &AbilityLevel, &Name)>,
+    evolutions: Res<Evolutions>,
+    implants: Res<Implants>,
+    installed_implants: Query<&Implant>,
+    learned: Query<&AbilityKind>,
+    stats: Query<&PlayerStats, With<Player>>,
+    mut commands: Commands,
+) {
     let num_choices = 3;
     //randomly choose abilities to level
     //player may or may not have them already
     // commands.entity(player_query.single_mut()).
     let mut rng = rand::thread_rng();
 
-    for (entity, ability, name) in abilities.iter().choose_multiple(&mut rng, num_choices) {
-        commands.spawn(LevelUpChoice {
-            entity_to_level: entity,
-        });
+    let all_abilities: Vec<(Entity, &AbilityLevel, &Name)> = abilities.iter().collect();
+    for (base, recipe) in evolutions.available(&all_abilities) {
+        commands.spawn(EvolutionChoice { base, recipe });
+    }
+
+    let installed: Vec<&Implant> = installed_implants.iter().collect();
+    let learned: Vec<AbilityKind> = learned.iter().copied().collect();
+    let stats = stats.get_single().cloned().unwrap_or_default();
+    let pool = abilities
+        .iter()
+        .filter(|(_, ability, _)| ability.level < MAX_ABILITY_LEVEL)
+        .map(|(entity, _, _)| LevelUpPoolEntry::Ability(entity))
+        .chain(available_to_learn(&learned, &stats).into_iter().map(LevelUpPoolEntry::Learn))
+        .chain(implants.available(&installed).into_iter().map(LevelUpPoolEntry::Implant));
+    for entry in pool.choose_multiple(&mut rng, num_choices) {
+        match entry {
+            LevelUpPoolEntry::Ability(entity) => {
+                commands.spawn(LevelUpChoice {
+                    entity_to_level: entity,
+                });
+            }
+            LevelUpPoolEntry::Learn(ability) => {
+                let category = ability.category();
+                commands.spawn(LearnAbilityChoice {
+                    ability,
+                    used_slots: learned.iter().filter(|learned| learned.category() == category).count(),
+                    slots: stats.slots(category.slot_stat()),
+                });
+            }
+            LevelUpPoolEntry::Implant(implant) => {
+                commands.spawn(ImplantChoice { implant });
+            }
+        }
     }
 }
 
-pub fn cleanup_level_up(mut commands: Commands, choices: Query<(Entity, &LevelUpChoice)>) {
+pub fn cleanup_level_up(
+    mut commands: Commands,
+    choices: Query<(Entity, &LevelUpChoice)>,
+    evolution_choices: Query<Entity, With<EvolutionChoice>>,
+    implant_choices: Query<Entity, With<ImplantChoice>>,
+    learn_choices: Query<Entity, With<LearnAbilityChoice>>,
+) {
     for (entity, choice) in choices.iter() {
         commands.entity(entity).despawn();
     }
+    for entity in evolution_choices.iter() {
+        commands.entity(entity).despawn();
+    }
+    for entity in implant_choices.iter() {
+        commands.entity(entity).despawn();
+    }
+    for entity in learn_choices.iter() {
+        commands.entity(entity).despawn();
+    }
 }
 
 pub fn pause_animations(mut animation_timers: ResMut<SpriteAnimController>) {
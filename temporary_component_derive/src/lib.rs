@@ -18,6 +18,14 @@ pub fn temporary_component_derive(input: TokenStream) -> TokenStream {
             fn is_finished(&self) -> bool {
                 self.timer.finished()
             }
+
+            fn timer(&self) -> &bevy::prelude::Timer {
+                &self.timer
+            }
+
+            fn timer_mut(&mut self) -> &mut bevy::prelude::Timer {
+                &mut self.timer
+            }
         }
     };
 